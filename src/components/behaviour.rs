@@ -0,0 +1,57 @@
+use bracket_lib::prelude::Point;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum AiState {
+    /// Stands still until something comes into view
+    Idle,
+    /// Roams at random until something comes into view
+    Wander,
+    /// Goes after its prey, and then wherever it last saw it
+    #[default]
+    Hunt,
+    /// Runs from anything it can see
+    Flee,
+    /// Stays near home until something comes into view
+    Guard,
+    /// Lies in wait until something comes very close
+    Ambush,
+}
+
+impl AiState {
+    pub fn describe(&self) -> &'static str {
+        match self {
+            AiState::Idle => "It seems idle.",
+            AiState::Wander => "It's wandering around.",
+            AiState::Hunt => "It's hunting!",
+            AiState::Flee => "It's trying to get away.",
+            AiState::Guard => "It's guarding this spot.",
+            AiState::Ambush => "It's lying in wait.",
+        }
+    }
+}
+
+/// Who a hostile goes after first, when it can see more than one target.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum Prey {
+    #[default]
+    Anyone,
+    Colonists,
+    SecBot,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Behaviour {
+    pub state: AiState,
+    /// State it starts in, and returns to once it loses its prey
+    pub default: AiState,
+    /// Flees once health drops below this percentage
+    pub flee_below: i32,
+    /// Backs away from targets closer than this, rather than attacking
+    pub keep_distance: i32,
+    pub prefers: Prey,
+    /// Where it spawned, for guards to return to
+    pub home: Point,
+    /// Where it last saw its prey
+    pub last_seen: Option<Point>,
+}
@@ -1,4 +1,5 @@
 use bracket_lib::prelude::RGB;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Blood(pub RGB);
@@ -1,4 +1,6 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Colonist {
     pub path: Option<Vec<usize>>,
-    pub weapon: Option<i32>,
 }
@@ -0,0 +1,24 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum DamageType {
+    #[default]
+    Kinetic,
+    Explosive,
+    Acid,
+    Fire,
+}
+
+/// Percentage of each damage type actually taken. 100 is normal, 0 is immune
+/// and anything over 100 is a weakness. Unlisted types do full damage.
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct Resistances(pub Vec<(DamageType, i32)>);
+
+impl Resistances {
+    pub fn apply(&self, damage: i32, damage_type: DamageType) -> i32 {
+        self.0
+            .iter()
+            .find(|(t, _)| *t == damage_type)
+            .map_or(damage, |(_, percent)| damage * percent / 100)
+    }
+}
@@ -0,0 +1,26 @@
+use serde::{Deserialize, Serialize};
+
+/// Energy needed to take an action; a standard action costs this much.
+pub const ACTION_COST: i32 = 100;
+
+/// Actors build up `speed` energy every turn, and act while they have at
+/// least `ACTION_COST` of it. Speed 100 is one action per turn.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Energy {
+    pub speed: i32,
+    pub energy: i32,
+}
+
+impl Energy {
+    /// Starts ready to act straight away.
+    pub fn new(speed: i32) -> Self {
+        Self {
+            speed,
+            energy: ACTION_COST,
+        }
+    }
+
+    pub fn ready(&self) -> bool {
+        self.energy >= ACTION_COST
+    }
+}
@@ -1,7 +1,11 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Explosive {
     pub range: i32,
 }
 
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Boom {
     pub range: i32,
 }
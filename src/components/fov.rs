@@ -1,7 +1,9 @@
+use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
 
 use bracket_lib::prelude::Point;
 
+#[derive(Clone, Serialize, Deserialize)]
 pub struct FieldOfView {
     pub radius: i32,
     pub visible_tiles: HashSet<Point>,
@@ -1,5 +1,7 @@
 use bracket_lib::prelude::*;
+use serde::{Deserialize, Serialize};
 
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Glyph {
     pub glyph: FontCharType,
     pub color: ColorPair,
@@ -0,0 +1,27 @@
+use legion::Entity;
+
+/// Damage a hostile has recently taken, and from whom. Fades a point a turn.
+#[derive(Clone)]
+pub struct Grudge(pub Vec<(Entity, i32)>);
+
+impl Grudge {
+    pub fn add(&mut self, attacker: Entity, damage: i32) {
+        if let Some(g) = self.0.iter_mut().find(|(e, _)| *e == attacker) {
+            g.1 += damage;
+        } else {
+            self.0.push((attacker, damage));
+        }
+    }
+
+    pub fn against(&self, target: Entity) -> i32 {
+        self.0
+            .iter()
+            .find(|(e, _)| *e == target)
+            .map_or(0, |(_, damage)| *damage)
+    }
+
+    pub fn fade(&mut self) {
+        self.0.iter_mut().for_each(|g| g.1 -= 1);
+        self.0.retain(|(_, damage)| *damage > 0);
+    }
+}
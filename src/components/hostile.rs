@@ -1,12 +1,32 @@
+use super::{DamageType, StatusEffect};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Hostile {
     pub melee: Vec<Melee>,
     pub ranged: Vec<Ranged>,
+    /// Route to the stairs, while following the player to another layer
+    pub path: Option<Vec<usize>>,
 }
 
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Melee {
     pub damage: i32,
+    #[serde(default)]
+    pub damage_type: DamageType,
+    /// Applied to whatever the attack hits
+    #[serde(default)]
+    pub inflicts: Option<StatusEffect>,
 }
 
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Ranged {
     pub power: i32,
+    /// Maximum distance the attack can reach
+    pub range: i32,
+    #[serde(default)]
+    pub damage_type: DamageType,
+    /// Applied to whatever the attack hits
+    #[serde(default)]
+    pub inflicts: Option<StatusEffect>,
 }
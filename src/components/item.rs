@@ -0,0 +1,35 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum ItemEffect {
+    /// Restores this much health
+    Heal(i32),
+    /// Arms a grenade
+    Grenade,
+    /// Opens locked doors. Never used up.
+    Keycard,
+    /// Adds this many rounds to a magazine weapon's reserve
+    Ammo(i32),
+    /// Doubles speed for this many turns
+    Haste(i32),
+}
+
+/// Something that can be picked up. On the ground it also has a `Position`;
+/// once picked up it lives in an `Inventory` instead.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Item {
+    pub id: String,
+    pub name: String,
+    pub effect: ItemEffect,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Inventory {
+    pub items: Vec<Item>,
+}
+
+impl Inventory {
+    pub fn has_keycard(&self) -> bool {
+        self.items.iter().any(|i| i.effect == ItemEffect::Keycard)
+    }
+}
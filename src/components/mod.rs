@@ -1,39 +1,55 @@
+mod behaviour;
 mod blood;
 mod colonist;
 mod colonist_status;
+mod damage;
 mod description;
 mod dialog;
+mod energy;
 mod explosive;
 mod fov;
 mod glyph;
+mod grudge;
 mod health;
 mod hostile;
+mod item;
 mod name;
 mod position;
 mod projectile;
 mod property_value;
+mod shield;
 mod speech;
+mod status_effect;
 mod tags;
 mod targeting;
 mod tile_trigger;
 mod timed_event;
+mod weapon;
 
+pub use behaviour::*;
 pub use blood::*;
 pub use colonist::*;
 pub use colonist_status::*;
+pub use damage::*;
 pub use description::Description;
 pub use dialog::*;
+pub use energy::*;
 pub use explosive::*;
 pub use fov::FieldOfView;
 pub use glyph::Glyph;
+pub use grudge::Grudge;
 pub use health::*;
 pub use hostile::*;
+pub use item::*;
 pub use name::*;
 pub use position::Position;
 pub use projectile::*;
 pub use property_value::*;
+pub use shield::*;
 pub use speech::*;
+pub use status_effect::*;
 pub use tags::*;
 pub use targeting::*;
 pub use tile_trigger::*;
 pub use timed_event::*;
+pub use weapon::*;
@@ -1,6 +1,7 @@
 use bracket_lib::prelude::Point;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct Position {
     pub pt: Point,
     pub layer: u32,
@@ -1,5 +1,7 @@
 use bracket_lib::prelude::Point;
+use serde::{Deserialize, Serialize};
 
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Projectile {
     pub path: Vec<Point>,
     pub layer: usize,
@@ -1 +1,4 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Serialize, Deserialize)]
 pub struct PropertyValue(pub i32);
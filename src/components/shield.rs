@@ -0,0 +1,36 @@
+use serde::{Deserialize, Serialize};
+
+/// An energy shield that soaks up damage before it reaches `Health`, and
+/// recharges once its owner has gone a few turns without being hit.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Shield {
+    pub max: i32,
+    pub current: i32,
+    /// Points restored per turn while recharging
+    pub recharge: i32,
+    /// Turns without a hit before recharging starts
+    pub delay: i32,
+    /// Turns left until recharging starts
+    pub cooldown: i32,
+}
+
+impl Shield {
+    /// Soaks up as much of the damage as it can, returning what gets through.
+    pub fn absorb(&mut self, damage: i32) -> i32 {
+        if damage < 1 {
+            return damage;
+        }
+        self.cooldown = self.delay;
+        let absorbed = i32::min(self.current, damage);
+        self.current -= absorbed;
+        damage - absorbed
+    }
+
+    pub fn tick(&mut self) {
+        if self.cooldown > 0 {
+            self.cooldown -= 1;
+        } else {
+            self.current = i32::min(self.max, self.current + self.recharge);
+        }
+    }
+}
@@ -0,0 +1,80 @@
+use serde::{Deserialize, Serialize};
+
+/// How far a blinded entity can see, at most
+pub const BLINDED_FOV: i32 = 2;
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum StatusKind {
+    /// Loses its turns
+    Stunned,
+    /// Takes fire damage every turn
+    Burning,
+    /// Takes acid damage every turn
+    Corroded,
+    /// Acts at half speed
+    Slowed,
+    /// Can barely see
+    Blinded,
+    /// Acts at double speed
+    Hasted,
+}
+
+impl StatusKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            StatusKind::Stunned => "Stunned",
+            StatusKind::Burning => "Burning",
+            StatusKind::Corroded => "Corroded",
+            StatusKind::Slowed => "Slowed",
+            StatusKind::Blinded => "Blinded",
+            StatusKind::Hasted => "Hasted",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub struct StatusEffect {
+    pub kind: StatusKind,
+    pub turns: i32,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct StatusEffects(pub Vec<StatusEffect>);
+
+impl StatusEffects {
+    pub fn has(&self, kind: StatusKind) -> bool {
+        self.0.iter().any(|e| e.kind == kind)
+    }
+
+    /// Re-applying an effect extends it rather than stacking it.
+    pub fn add(&mut self, effect: StatusEffect) {
+        if let Some(e) = self.0.iter_mut().find(|e| e.kind == effect.kind) {
+            e.turns = i32::max(e.turns, effect.turns);
+        } else {
+            self.0.push(effect);
+        }
+    }
+
+    /// How much energy a turn brings, given the owner's usual speed.
+    pub fn speed(&self, speed: i32) -> i32 {
+        if self.has(StatusKind::Stunned) {
+            return 0;
+        }
+        let mut speed = speed;
+        if self.has(StatusKind::Slowed) {
+            speed /= 2;
+        }
+        if self.has(StatusKind::Hasted) {
+            speed *= 2;
+        }
+        speed
+    }
+
+    pub fn fov_radius(&self, radius: i32) -> i32 {
+        if self.has(StatusKind::Blinded) {
+            i32::min(radius, BLINDED_FOV)
+        } else {
+            radius
+        }
+    }
+}
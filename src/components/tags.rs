@@ -2,6 +2,9 @@ pub struct Player;
 
 pub struct Door;
 
+/// A door that needs a keycard to open
+pub struct Locked;
+
 pub struct Targetable;
 
 pub struct Active;
@@ -1,5 +1,6 @@
 use legion::Entity;
 
+#[derive(Clone)]
 pub struct Targeting {
     pub targets: Vec<(Entity, f32)>, // (entity / distance)
     pub current_target: Option<Entity>,
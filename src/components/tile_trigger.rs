@@ -1,6 +1,10 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Serialize, Deserialize)]
 pub enum TriggerType {
     EndGame,
     Healing,
 }
 
+#[derive(Clone, Serialize, Deserialize)]
 pub struct TileTrigger(pub TriggerType);
@@ -1,8 +1,12 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Serialize, Deserialize)]
 pub struct TimedEvent {
     pub timer: i32,
     pub event: EventType,
 }
 
+#[derive(Clone, Serialize, Deserialize)]
 pub enum EventType {
     Boom,
     HatchXenomorph,
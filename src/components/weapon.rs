@@ -0,0 +1,167 @@
+use super::DamageType;
+use bracket_lib::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum FireMode {
+    Single,
+    /// Several aimed shots in one turn
+    Burst(i32),
+    /// Pellets fanned out around the target
+    Spread(i32),
+}
+
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Feed {
+    /// Rounds loaded from a finite reserve
+    Magazine {
+        capacity: i32,
+        loaded: i32,
+        reserve: i32,
+    },
+    /// Builds heat with every trigger pull and cools off a little each turn
+    Heat {
+        limit: i32,
+        heat: i32,
+        per_shot: i32,
+        cooling: i32,
+    },
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Weapon {
+    pub name: String,
+    pub power: i32,
+    /// Distance before power and accuracy start to fall off
+    pub range: i32,
+    /// Chance to hit (out of 100) within range
+    pub accuracy: i32,
+    /// Number of walls a shot can punch through
+    pub penetration: i32,
+    pub damage_type: DamageType,
+    pub glyph: FontCharType,
+    pub color: RGB,
+    pub mode: FireMode,
+    pub feed: Feed,
+    /// Turns it takes to reload or vent
+    pub reload_turns: i32,
+    /// Turns left on the current reload, if any
+    pub reloading: i32,
+}
+
+impl Weapon {
+    pub fn can_fire(&self) -> bool {
+        self.reloading == 0
+            && match self.feed {
+                Feed::Magazine { loaded, .. } => loaded > 0,
+                Feed::Heat {
+                    limit,
+                    heat,
+                    per_shot,
+                    ..
+                } => heat + per_shot <= limit,
+            }
+    }
+
+    /// Spends ammo (or builds heat) for one trigger pull, returning how many
+    /// rounds actually go downrange.
+    pub fn use_ammo(&mut self) -> i32 {
+        let wanted = match self.mode {
+            FireMode::Burst(n) => n,
+            _ => 1,
+        };
+        match &mut self.feed {
+            Feed::Magazine { loaded, .. } => {
+                let rounds = i32::min(wanted, *loaded);
+                *loaded -= rounds;
+                rounds
+            }
+            Feed::Heat { heat, per_shot, .. } => {
+                *heat += *per_shot;
+                wanted
+            }
+        }
+    }
+
+    pub fn can_reload(&self) -> bool {
+        self.reloading == 0
+            && match self.feed {
+                Feed::Magazine {
+                    capacity,
+                    loaded,
+                    reserve,
+                } => loaded < capacity && reserve > 0,
+                Feed::Heat { heat, .. } => heat > 0,
+            }
+    }
+
+    /// Adds spare rounds. Only magazine weapons take ammo.
+    pub fn resupply(&mut self, rounds: i32) -> bool {
+        if let Feed::Magazine { reserve, .. } = &mut self.feed {
+            *reserve += rounds;
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn start_reload(&mut self) {
+        self.reloading = self.reload_turns;
+    }
+
+    /// Out of rounds with nothing left to reload from.
+    pub fn is_dry(&self) -> bool {
+        matches!(
+            self.feed,
+            Feed::Magazine {
+                loaded: 0,
+                reserve: 0,
+                ..
+            }
+        )
+    }
+
+    /// Called once per turn: cools the barrel and finishes reloads.
+    pub fn tick(&mut self) {
+        if let Feed::Heat { heat, cooling, .. } = &mut self.feed {
+            *heat = i32::max(0, *heat - *cooling);
+        }
+        if self.reloading > 0 {
+            self.reloading -= 1;
+            if self.reloading == 0 {
+                match &mut self.feed {
+                    Feed::Magazine {
+                        capacity,
+                        loaded,
+                        reserve,
+                    } => {
+                        let taken = i32::min(*capacity - *loaded, *reserve);
+                        *loaded += taken;
+                        *reserve -= taken;
+                    }
+                    Feed::Heat { heat, .. } => *heat = 0,
+                }
+            }
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Loadout {
+    pub weapons: Vec<Weapon>,
+    pub current: usize,
+}
+
+impl Loadout {
+    pub fn active(&self) -> &Weapon {
+        &self.weapons[self.current]
+    }
+
+    pub fn active_mut(&mut self) -> &mut Weapon {
+        &mut self.weapons[self.current]
+    }
+
+    pub fn cycle(&mut self) {
+        self.current = (self.current + 1) % self.weapons.len();
+    }
+}
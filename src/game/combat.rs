@@ -21,40 +21,283 @@ pub fn player_open_fire_at_target(ecs: &mut World, map: &mut Map) -> NewState {
         return NewState::Wait;
     }
 
-    ranged_attack(ecs, map, player_entity.unwrap(), target.unwrap(), 20);
+    // Empty or overheated guns don't use up a turn
+    if !fire_loadout(ecs, map, player_entity.unwrap(), target.unwrap()) {
+        return NewState::Wait;
+    }
 
     NewState::Player
 }
 
-pub fn ranged_attack(
+/// Fires the attacker's active weapon, spending ammo or building heat.
+/// Returns false if the weapon isn't ready to fire.
+pub fn fire_loadout(ecs: &mut World, map: &mut Map, attacker: Entity, victim: Entity) -> bool {
+    let mut ready = None;
+    if let Ok(mut entry) = ecs.entry_mut(attacker) {
+        if let Ok(loadout) = entry.get_component_mut::<Loadout>() {
+            let weapon = loadout.active_mut();
+            if weapon.can_fire() {
+                let rounds = weapon.use_ammo();
+                ready = Some((weapon.clone(), rounds));
+            }
+        }
+    }
+
+    if let Some((weapon, rounds)) = ready {
+        fire_weapon(ecs, map, attacker, victim, &weapon, rounds);
+        true
+    } else {
+        false
+    }
+}
+
+/// Starts reloading (or venting) the active weapon. Returns false if there's
+/// nothing to reload.
+pub fn reload_loadout(ecs: &mut World, entity: Entity) -> bool {
+    let mut reloaded = false;
+    if let Ok(mut entry) = ecs.entry_mut(entity) {
+        if let Ok(loadout) = entry.get_component_mut::<Loadout>() {
+            let weapon = loadout.active_mut();
+            if weapon.can_reload() {
+                weapon.start_reload();
+                reloaded = true;
+            }
+        }
+    }
+    reloaded
+}
+
+/// Recharges shields that haven't been hit lately. Runs once per turn.
+pub fn tick_shields(ecs: &mut World) {
+    <&mut Shield>::query().for_each_mut(ecs, |shield| shield.tick());
+}
+
+/// Lets old grudges fade. Runs once per turn.
+pub fn tick_grudges(ecs: &mut World) {
+    let mut commands = CommandBuffer::new(ecs);
+    <(Entity, &mut Grudge)>::query().for_each_mut(ecs, |(entity, grudge)| {
+        grudge.fade();
+        if grudge.0.is_empty() {
+            commands.remove_component::<Grudge>(*entity);
+        }
+    });
+    commands.flush(ecs);
+}
+
+/// Cools every weapon and moves reloads along. Runs once per turn.
+pub fn tick_weapons(ecs: &mut World) {
+    <&mut Loadout>::query().for_each_mut(ecs, |loadout| {
+        loadout.weapons.iter_mut().for_each(|w| w.tick());
+    });
+}
+
+/// Everything a single projectile needs to know about the gun that fired it.
+pub struct Shot {
+    pub power: i32,
+    pub range: i32,
+    pub accuracy: i32,
+    pub penetration: i32,
+    pub damage_type: DamageType,
+    pub inflicts: Option<StatusEffect>,
+    pub glyph: FontCharType,
+    pub color: RGB,
+}
+
+impl Shot {
+    /// A generic shot, for anything that doesn't carry a `Loadout`.
+    pub fn basic(power: i32) -> Self {
+        Self {
+            power,
+            range: 5,
+            accuracy: 90,
+            penetration: 0,
+            damage_type: DamageType::Kinetic,
+            inflicts: None,
+            glyph: to_cp437('*'),
+            color: RED.into(),
+        }
+    }
+
+    pub fn from_weapon(weapon: &Weapon) -> Self {
+        Self {
+            power: weapon.power,
+            range: weapon.range,
+            accuracy: weapon.accuracy,
+            penetration: weapon.penetration,
+            damage_type: weapon.damage_type,
+            inflicts: None,
+            glyph: weapon.glyph,
+            color: weapon.color,
+        }
+    }
+}
+
+/// Fires a weapon at a target, according to its fire mode. `rounds` is how
+/// many shots of a burst were loaded.
+pub fn fire_weapon(
     ecs: &mut World,
     map: &mut Map,
     attacker: Entity,
     victim: Entity,
-    ranged_power: i32,
+    weapon: &Weapon,
+    rounds: i32,
 ) {
+    let shot = Shot::from_weapon(weapon);
+    match weapon.mode {
+        FireMode::Single => aimed_attack(ecs, map, attacker, victim, &shot),
+        FireMode::Burst(_) => {
+            for _ in 0..rounds {
+                aimed_attack(ecs, map, attacker, victim, &shot);
+            }
+        }
+        FireMode::Spread(n) => {
+            let (attacker_pos, victim_pos) = match positions(ecs, attacker, victim) {
+                Some(p) => p,
+                None => return,
+            };
+            // The centre pellet is aimed; the rest fan out to either side
+            aimed_attack(ecs, map, attacker, victim, &shot);
+            use ultraviolet::Vec2;
+            let delta = victim_pos.pt - attacker_pos.pt;
+            let across = Vec2::new(-delta.y as f32, delta.x as f32).normalized();
+            for i in 1..n {
+                let offset = across * (((i + 1) / 2) as f32) * if i % 2 == 0 { -1.0 } else { 1.0 };
+                let aim =
+                    victim_pos.pt + Point::new(offset.x.round() as i32, offset.y.round() as i32);
+                if map.get_current().in_bounds(aim) {
+                    fire_projectile(ecs, map, attacker, attacker_pos, aim, &shot, None);
+                }
+            }
+        }
+    }
+}
+
+fn positions(ecs: &World, attacker: Entity, victim: Entity) -> Option<(Position, Position)> {
     let mut attacker_pos = None;
     let mut victim_pos = None;
 
-    // Find positions for the start and end
     if let Ok(ae) = ecs.entry_ref(attacker) {
         if let Ok(pos) = ae.get_component::<Position>() {
-            attacker_pos = Some(pos.clone());
+            attacker_pos = Some(*pos);
         }
     }
     if let Ok(ae) = ecs.entry_ref(victim) {
         if let Ok(pos) = ae.get_component::<Position>() {
-            victim_pos = Some(pos.clone());
+            victim_pos = Some(*pos);
         }
     }
-    if attacker_pos.is_none() || victim_pos.is_none() {
+    Some((attacker_pos?, victim_pos?))
+}
+
+/// Returns the chance (out of 100) that `shooter` hits `target`, and the range.
+/// Shooters with a `Loadout` use their active weapon's accuracy.
+pub fn hit_probability(ecs: &World, shooter: Entity, target: Entity) -> (u32, u32) {
+    let (shooter_pos, target_pos) = match positions(ecs, shooter, target) {
+        Some(p) => p,
+        None => return (0, 0),
+    };
+
+    let range = DistanceAlg::Pythagoras.distance2d(shooter_pos.pt, target_pos.pt) as u32;
+
+    let shot = ecs
+        .entry_ref(shooter)
+        .ok()
+        .and_then(|e| {
+            e.get_component::<Loadout>()
+                .ok()
+                .map(|l| Shot::from_weapon(l.active()))
+        })
+        .unwrap_or_else(|| Shot::basic(0));
+
+    let mut hit_chance: i32 = shot.accuracy;
+    if range as i32 > shot.range {
+        hit_chance -= (range as i32 - shot.range) * 5;
+    }
+
+    (i32::max(5, hit_chance) as u32, range)
+}
+
+/// A ranged attack that has to roll to hit. Misses go wide of the target and
+/// carry on along the deviated line, hitting whatever they find.
+pub fn aimed_attack(ecs: &mut World, map: &mut Map, attacker: Entity, victim: Entity, shot: &Shot) {
+    let (attacker_pos, victim_pos) = match positions(ecs, attacker, victim) {
+        Some(p) => p,
+        None => return,
+    };
+    let (hit_chance, range) = hit_probability(ecs, attacker, victim);
+
+    let mut rng_lock = crate::RNG.lock();
+    let rng = rng_lock.as_mut().unwrap();
+    if rng.range(1, 101) <= hit_chance {
+        drop(rng_lock);
+        fire_projectile(ecs, map, attacker, attacker_pos, victim_pos.pt, shot, None);
+        return;
+    }
+
+    // Scatter gets worse with range
+    let spread = 1 + range as i32 / 5;
+    let mut aim = victim_pos.pt;
+    while aim == victim_pos.pt || aim == attacker_pos.pt || !map.get_current().in_bounds(aim) {
+        aim = victim_pos.pt
+            + Point::new(
+                rng.range(-spread, spread + 1),
+                rng.range(-spread, spread + 1),
+            );
+    }
+    drop(rng_lock);
+    fire_projectile(
+        ecs,
+        map,
+        attacker,
+        attacker_pos,
+        aim,
+        shot,
+        Some(victim_pos.pt),
+    );
+}
+
+/// A ranged attack that always flies straight at the victim.
+pub fn ranged_attack(
+    ecs: &mut World,
+    map: &mut Map,
+    attacker: Entity,
+    victim: Entity,
+    attack: &Ranged,
+) {
+    if let Some((attacker_pos, victim_pos)) = positions(ecs, attacker, victim) {
+        fire_projectile(
+            ecs,
+            map,
+            attacker,
+            attacker_pos,
+            victim_pos.pt,
+            &Shot {
+                damage_type: attack.damage_type,
+                inflicts: attack.inflicts,
+                ..Shot::basic(attack.power)
+            },
+            None,
+        );
+    }
+}
+
+// `missed` is a tile the shot passes without hitting anything.
+fn fire_projectile(
+    ecs: &mut World,
+    map: &mut Map,
+    attacker: Entity,
+    attacker_pos: Position,
+    aim: Point,
+    shot: &Shot,
+    missed: Option<Point>,
+) {
+    if attacker_pos.pt == aim {
         return;
     }
-    let attacker_pos = attacker_pos.unwrap();
-    let victim_pos = victim_pos.unwrap();
 
     // Set state for the projectile path
-    let mut power = ranged_power;
+    let mut power = shot.power;
+    let mut penetration = shot.penetration;
     let mut range = 0;
     let mut projectile_path = Vec::new();
     let mut splatter = None;
@@ -62,63 +305,116 @@ pub fn ranged_attack(
     let current_layer = attacker_pos.layer;
 
     // Map of entity locations. Rebuilt every time because it might change.
-    let pos_map = <(&Position, &Health)>::query()
+    let mut pos_map = <(&Position, &Health)>::query()
+        .filter(!component::<Door>())
         .iter(ecs)
         .map(|(pos, _)| pos.pt)
         .collect::<HashSet<Point>>();
+    if let Some(missed) = missed {
+        pos_map.remove(&missed);
+    }
 
     // Plot the initial trajectory
-    line2d_bresenham(attacker_pos.pt, victim_pos.pt)
-        .iter()
-        .skip(1)
-        .for_each(|pt| {
-            projectile_path.push(*pt);
-            if pos_map.contains(&pt) {
-                power -=
-                    hit_tile_contents(ecs, *pt, current_layer, &mut commands, &mut splatter, power);
-                if power < 1 {
-                    power = 1;
-                    range += 200;
-                }
+    let mut stopped = false;
+    for pt in line2d_bresenham(attacker_pos.pt, aim).iter().skip(1) {
+        projectile_path.push(*pt);
+        if hit_obstacle(
+            ecs,
+            map,
+            *pt,
+            current_layer,
+            &mut power,
+            &mut penetration,
+            &mut commands,
+        ) {
+            stopped = true;
+            break;
+        }
+        if pos_map.contains(pt) {
+            power -= hit_tile_contents(
+                ecs,
+                *pt,
+                current_layer,
+                &mut commands,
+                &mut splatter,
+                power,
+                shot.damage_type,
+            );
+            if let Some(effect) = shot.inflicts {
+                super::status_effects::afflict_tile(ecs, *pt, current_layer, effect);
             }
-            if let Some(bsplatter) = &mut splatter {
-                let idx = map.get_current().point2d_to_index(*pt);
-                map.get_current_mut().tiles[idx].color.fg = bsplatter.to_rgba(1.0);
-                bsplatter.r = f32::max(0.0, bsplatter.r - 0.1);
-                bsplatter.g = f32::max(0.0, bsplatter.g - 0.1);
-                bsplatter.b = f32::max(0.0, bsplatter.b - 0.1);
-                if bsplatter.r + bsplatter.g + bsplatter.b < 0.1 {
-                    splatter = None;
-                }
+            bear_grudge_tile(ecs, *pt, current_layer, attacker, shot.power);
+            if power < 1 {
+                power = 1;
+                range += 200;
             }
-            range += 1;
-            if range > 5 {
-                power -= 1;
-                if power < 1 {
-                    power = 1;
-                }
+        }
+        if let Some(bsplatter) = &mut splatter {
+            let layer = map.get_layer_mut(current_layer as usize);
+            let idx = layer.point2d_to_index(*pt);
+            layer.tiles[idx].color.fg = bsplatter.to_rgba(1.0);
+            bsplatter.r = f32::max(0.0, bsplatter.r - 0.1);
+            bsplatter.g = f32::max(0.0, bsplatter.g - 0.1);
+            bsplatter.b = f32::max(0.0, bsplatter.b - 0.1);
+            if bsplatter.r + bsplatter.g + bsplatter.b < 0.1 {
+                splatter = None;
             }
-        });
+        }
+        range += 1;
+        if range > shot.range {
+            power -= 1;
+            if power < 1 {
+                power = 1;
+            }
+        }
+    }
 
     // The trajectory can continue if we have power left
     use ultraviolet::Vec2;
-    let mut projectile_pos: Vec2 = Vec2::new(victim_pos.pt.x as f32, victim_pos.pt.y as f32);
+    let mut projectile_pos: Vec2 = Vec2::new(aim.x as f32, aim.y as f32);
     let slope = (projectile_pos - Vec2::new(attacker_pos.pt.x as f32, attacker_pos.pt.y as f32))
         .normalized();
-    while range < 25 && power > 0 {
+    while !stopped && range < 25 && power > 0 {
         projectile_pos += slope;
         let pt = Point::new(projectile_pos.x as i32, projectile_pos.y as i32);
+        if !map.get_layer(current_layer as usize).in_bounds(pt) {
+            break;
+        }
         projectile_path.push(pt);
+        if hit_obstacle(
+            ecs,
+            map,
+            pt,
+            current_layer,
+            &mut power,
+            &mut penetration,
+            &mut commands,
+        ) {
+            break;
+        }
         if pos_map.contains(&pt) {
-            power -= hit_tile_contents(ecs, pt, current_layer, &mut commands, &mut splatter, power);
+            power -= hit_tile_contents(
+                ecs,
+                pt,
+                current_layer,
+                &mut commands,
+                &mut splatter,
+                power,
+                shot.damage_type,
+            );
+            if let Some(effect) = shot.inflicts {
+                super::status_effects::afflict_tile(ecs, pt, current_layer, effect);
+            }
+            bear_grudge_tile(ecs, pt, current_layer, attacker, shot.power);
             if power < 1 {
                 power = 1;
                 range += 200;
             }
         }
         if let Some(bsplatter) = &mut splatter {
-            let idx = map.get_current().point2d_to_index(pt);
-            map.get_current_mut().tiles[idx].color.fg = bsplatter.to_rgba(1.0);
+            let layer = map.get_layer_mut(current_layer as usize);
+            let idx = layer.point2d_to_index(pt);
+            layer.tiles[idx].color.fg = bsplatter.to_rgba(1.0);
             bsplatter.r = f32::max(0.0, bsplatter.r - 0.1);
             bsplatter.g = f32::max(0.0, bsplatter.g - 0.1);
             bsplatter.b = f32::max(0.0, bsplatter.b - 0.1);
@@ -126,13 +422,8 @@ pub fn ranged_attack(
                 splatter = None;
             }
         }
-        let idx = map.get_current().point2d_to_index(pt);
-        if map.get_current().tiles[idx].tile_type == TileType::Wall {
-            range += 100;
-            power = 0;
-        }
         range += 1;
-        if range > 5 {
+        if range > shot.range {
             power -= 1;
             if power < 1 {
                 power = 1;
@@ -147,14 +438,89 @@ pub fn ranged_attack(
             layer: current_layer as usize,
         },
         Glyph {
-            glyph: to_cp437('*'),
-            color: ColorPair::new(RED, BLACK),
+            glyph: shot.glyph,
+            color: ColorPair::new(shot.color, BLACK),
         },
     ));
 
     commands.flush(ecs);
 }
 
+/// Knocks `power` off the door on a tile, blowing it open once its health
+/// runs out. Returns true if whatever hit it was stopped.
+pub fn hit_door(
+    ecs: &mut World,
+    map: &mut Map,
+    pt: Point,
+    layer: u32,
+    power: &mut i32,
+    commands: &mut CommandBuffer,
+) -> bool {
+    let map_layer = map.get_layer_mut(layer as usize);
+    let idx = map_layer.point2d_to_index(pt);
+    let mut door = None;
+    <(Entity, &Position, &Door, &mut Health)>::query()
+        .iter_mut(ecs)
+        .filter(|(_, pos, _, _)| pos.layer == layer && pos.pt == pt)
+        .for_each(|(entity, _, _, hp)| {
+            let absorbed = i32::min(hp.current, *power);
+            hp.current -= absorbed;
+            *power -= absorbed;
+            door = Some((*entity, hp.current));
+        });
+    match door {
+        Some((entity, 0)) => {
+            map_layer.open_door(idx);
+            map_layer.tiles[idx].glyph = to_cp437('/');
+            commands.remove(entity);
+            *power < 1
+        }
+        Some(_) => true,
+        None => true, // A door without health is a wall
+    }
+}
+
+/// Checks the tile itself for anything that stops or slows a shot. Windows
+/// shatter, doors soak up damage until they break open, and walls stop the
+/// shot unless it has penetration left. Returns true if the shot stops here.
+fn hit_obstacle(
+    ecs: &mut World,
+    map: &mut Map,
+    pt: Point,
+    layer: u32,
+    power: &mut i32,
+    penetration: &mut i32,
+    commands: &mut CommandBuffer,
+) -> bool {
+    let map_layer = map.get_layer_mut(layer as usize);
+    let idx = map_layer.point2d_to_index(pt);
+
+    if map_layer.is_door[idx] {
+        return hit_door(ecs, map, pt, layer, power, commands);
+    }
+
+    if map_layer.tiles[idx].tile_type == TileType::Window {
+        map_layer.tiles[idx] = Tile::broken_window();
+        *power -= 2;
+        <(&Position, &mut Description)>::query()
+            .iter_mut(ecs)
+            .filter(|(pos, _)| pos.layer == layer && pos.pt == pt)
+            .for_each(|(_, d)| d.0 = "A shattered window. Mind the glass.".to_string());
+        return *power < 1;
+    }
+
+    if map_layer.tiles[idx].opaque {
+        if *penetration > 0 {
+            *penetration -= 1;
+            *power /= 2;
+            return *power < 1;
+        }
+        return true;
+    }
+
+    false
+}
+
 pub fn hit_tile_contents(
     ecs: &mut World,
     pt: Point,
@@ -162,29 +528,51 @@ pub fn hit_tile_contents(
     commands: &mut CommandBuffer,
     splatter: &mut Option<RGB>,
     power: i32,
+    damage_type: DamageType,
 ) -> i32 {
     let mut rng_lock = crate::RNG.lock();
     let rng = rng_lock.as_mut().unwrap();
 
     let mut power_loss = 0;
     let mut dead_entities = Vec::new();
-    <(Entity, &Position, &mut Health)>::query()
-        .iter_mut(ecs)
-        .filter(|(_, pos, _)| pos.layer == layer && pos.pt == pt)
-        .for_each(|(entity, _, hp)| {
-            power_loss += hp.current;
-            if power_loss < 0 {
-                power_loss = 0;
-            }
-            let damage = i32::max(0, power + rng.roll_dice(1, 4) - 2);
-            //println!("{}", damage);
-            hp.current -= damage;
-            if hp.current < 0 {
-                hp.current = 0;
-                dead_entities.push(*entity);
-            }
-        });
+    <(
+        Entity,
+        &Position,
+        &mut Health,
+        Option<&Resistances>,
+        Option<&mut Shield>,
+    )>::query()
+    .filter(!component::<Door>())
+    .iter_mut(ecs)
+    .filter(|(_, pos, _, _, _)| pos.layer == layer && pos.pt == pt)
+    .for_each(|(entity, _, hp, resistances, shield)| {
+        power_loss += hp.current;
+        if power_loss < 0 {
+            power_loss = 0;
+        }
+        let mut damage = i32::max(0, power + rng.roll_dice(1, 4) - 2);
+        if let Some(resistances) = resistances {
+            damage = resistances.apply(damage, damage_type);
+        }
+        if let Some(shield) = shield {
+            damage = shield.absorb(damage);
+        }
+        //println!("{}", damage);
+        hp.current -= damage;
+        if hp.current < 0 {
+            hp.current = 0;
+            dead_entities.push(*entity);
+        }
+    });
 
+    detonate(ecs, commands, &dead_entities);
+    kill_things(ecs, commands, dead_entities, splatter, rng);
+
+    power_loss
+}
+
+// Anything explosive that just died goes boom.
+fn detonate(ecs: &World, commands: &mut CommandBuffer, dead_entities: &[Entity]) {
     dead_entities.iter().for_each(|e| {
         if let Ok(er) = ecs.entry_ref(*e) {
             if let Ok(boom) = er.get_component::<Explosive>() {
@@ -197,13 +585,9 @@ pub fn hit_tile_contents(
             }
         }
     });
-
-    kill_things(ecs, commands, dead_entities, splatter);
-
-    power_loss
 }
 
-pub fn melee(ecs: &mut World, map: &mut Map, attacker: Entity, victim: Entity, melee_power: i32) {
+pub fn melee(ecs: &mut World, map: &mut Map, attacker: Entity, victim: Entity, attack: &Melee) {
     // Check range and validity
     let mut attacker_pos = None;
     let mut defender_pos = None;
@@ -232,27 +616,78 @@ pub fn melee(ecs: &mut World, map: &mut Map, attacker: Entity, victim: Entity, m
         return; // Too far away, bail
     }
 
+    // Splatter blood. It's good for you.
+    if let Ok(v) = ecs.entry_ref(victim) {
+        if let Ok(blood) = v.get_component::<Blood>() {
+            let idx = map.get_layer(dpos.layer as usize).point2d_to_index(dpos.pt);
+            map.get_layer_mut(dpos.layer as usize).tiles[idx].color.fg = blood.0.into();
+        }
+    }
+
     // Inflict damage upon the hapless victim
+    if let Some(effect) = attack.inflicts {
+        super::status_effects::afflict(ecs, victim, effect);
+    }
+    bear_grudge(ecs, victim, attacker, attack.damage);
+    damage_entity(ecs, victim, attack.damage, attack.damage_type);
+}
+
+/// Hostiles remember who hurt them, so they can turn on them.
+fn bear_grudge(ecs: &mut World, victim: Entity, attacker: Entity, damage: i32) {
+    if victim == attacker || damage < 1 {
+        return;
+    }
+    if let Some(mut entry) = ecs.entry(victim) {
+        if entry.get_component::<Hostile>().is_err() {
+            return;
+        }
+        if let Ok(grudge) = entry.get_component_mut::<Grudge>() {
+            grudge.add(attacker, damage);
+        } else {
+            entry.add_component(Grudge(vec![(attacker, damage)]));
+        }
+    }
+}
+
+fn bear_grudge_tile(ecs: &mut World, pt: Point, layer: u32, attacker: Entity, damage: i32) {
+    let victims: Vec<Entity> = <(Entity, &Position, &Hostile)>::query()
+        .iter(ecs)
+        .filter(|(_, pos, _)| pos.layer == layer && pos.pt == pt)
+        .map(|(e, _, _)| *e)
+        .collect();
+    victims
+        .iter()
+        .for_each(|e| bear_grudge(ecs, *e, attacker, damage));
+}
+
+/// Deals damage straight to one entity, after resistances, killing it if
+/// necessary.
+pub fn damage_entity(ecs: &mut World, victim: Entity, damage: i32, damage_type: DamageType) {
     let mut dead_entities = Vec::new();
     if let Ok(mut v) = ecs.entry_mut(victim) {
+        let mut damage = v
+            .get_component::<Resistances>()
+            .map_or(damage, |r| r.apply(damage, damage_type));
+        if let Ok(shield) = v.get_component_mut::<Shield>() {
+            damage = shield.absorb(damage);
+        }
         if let Ok(hp) = v.get_component_mut::<Health>() {
-            hp.current = i32::max(0, hp.current - melee_power);
+            hp.current = i32::max(0, hp.current - damage);
             if hp.current == 0 {
                 dead_entities.push(victim);
             }
         }
-        if let Ok(blood) = v.get_component::<Blood>() {
-            let idx = map.get_layer(dpos.layer as usize).point2d_to_index(dpos.pt);
-            map.get_layer_mut(dpos.layer as usize).tiles[idx].color.fg = blood.0.into();
-        }
     }
 
     // If necessary, kill them.
     let mut commands = CommandBuffer::new(ecs);
     let mut splatter = None;
-    kill_things(ecs, &mut commands, dead_entities, &mut splatter);
-
-    // Splatter blood. It's good for you.
+    let mut rng_lock = crate::RNG.lock();
+    let rng = rng_lock.as_mut().unwrap();
+    detonate(ecs, &mut commands, &dead_entities);
+    kill_things(ecs, &mut commands, dead_entities, &mut splatter, rng);
+    drop(rng_lock);
+    commands.flush(ecs);
 }
 
 fn kill_things(
@@ -260,6 +695,7 @@ fn kill_things(
     commands: &mut CommandBuffer,
     dead_entities: Vec<Entity>,
     splatter: &mut Option<RGB>,
+    rng: &mut RandomNumberGenerator,
 ) {
     dead_entities.iter().for_each(|entity| {
         crate::stats::record_death();
@@ -280,7 +716,6 @@ fn kill_things(
             }
             if was_colonist {
                 if let Ok(d) = er.get_component_mut::<Description>() {
-                    let mut rng = RandomNumberGenerator::new();
                     if rng.range(0, 10) < 5 {
                         d.0 = format!(
                             "{} They left behind a spouse and {} children.",
@@ -311,6 +746,7 @@ fn kill_things(
             commands.remove_component::<Targetable>(*entity);
             commands.remove_component::<Explosive>(*entity);
             commands.remove_component::<TimedEvent>(*entity);
+            commands.remove_component::<StatusEffects>(*entity);
         }
         if was_decor {
             crate::stats::record_prop_death();
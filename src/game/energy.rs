@@ -0,0 +1,141 @@
+use crate::components::*;
+use legion::*;
+
+pub const MOVE_COST: i32 = ACTION_COST;
+pub const MELEE_COST: i32 = ACTION_COST;
+pub const FIRE_COST: i32 = ACTION_COST;
+pub const THROW_COST: i32 = ACTION_COST;
+pub const STAIRS_COST: i32 = ACTION_COST;
+pub const WAIT_COST: i32 = ACTION_COST;
+pub const RELOAD_COST: i32 = ACTION_COST / 2;
+pub const DOOR_COST: i32 = ACTION_COST / 2;
+pub const ITEM_COST: i32 = ACTION_COST / 2;
+
+/// Energy can't be banked beyond this, so idle actors don't store up turns.
+const MAX_ENERGY: i32 = ACTION_COST * 2;
+
+pub fn player_ready(ecs: &World) -> bool {
+    <(&Player, &Energy)>::query()
+        .iter(ecs)
+        .any(|(_, energy)| energy.ready())
+}
+
+pub fn spend_player(ecs: &mut World, cost: i32) {
+    <(&Player, &mut Energy)>::query().for_each_mut(ecs, |(_, energy)| energy.energy -= cost);
+}
+
+/// Charges a single actor for an action.
+pub fn spend(ecs: &mut World, entity: Entity, cost: i32) {
+    if let Some(mut entry) = ecs.entry(entity) {
+        if let Ok(energy) = entry.get_component_mut::<Energy>() {
+            energy.energy -= cost;
+        }
+    }
+}
+
+/// Everyone who can take a turn, and how they decide what to do.
+#[derive(Clone, Copy, PartialEq)]
+pub enum Actor {
+    Player,
+    Friendly(Entity),
+    Colonist(Entity),
+    Monster(Entity),
+}
+
+/// The actors that were ready when the enemy turn began, ordered so the
+/// ready actor with the most energy goes next. Fast actors get their extra
+/// actions in between everyone else's rather than all at once. Ties go to
+/// the lowest entity, so replays always play out in the same order.
+pub struct Queue {
+    ready: Vec<(i32, Entity, Actor)>,
+    acting: Option<(Entity, Actor)>,
+}
+
+impl Queue {
+    /// Built once per enemy turn, so picking each actor doesn't mean looking
+    /// at everyone again.
+    pub fn new(ecs: &World) -> Self {
+        let mut ready: Vec<(i32, Entity, Actor)> = <(
+            Entity,
+            &Energy,
+            Option<&Player>,
+            Option<&Friendly>,
+            Option<&Colonist>,
+            Option<&Hostile>,
+        )>::query()
+        .filter(component::<Active>() | component::<Player>())
+        .iter(ecs)
+        .filter(|(_, energy, ..)| energy.ready())
+        .filter_map(|(entity, energy, player, friendly, colonist, hostile)| {
+            let actor = if player.is_some() {
+                Actor::Player
+            } else if friendly.is_some() {
+                Actor::Friendly(*entity)
+            } else if colonist.is_some() {
+                Actor::Colonist(*entity)
+            } else if hostile.is_some() {
+                Actor::Monster(*entity)
+            } else {
+                return None;
+            };
+            Some((energy.energy, *entity, actor))
+        })
+        .collect();
+        // Whoever goes first sits at the end
+        ready.sort_by(|a, b| Self::order(a, b));
+        Self {
+            ready,
+            acting: None,
+        }
+    }
+
+    fn order(a: &(i32, Entity, Actor), b: &(i32, Entity, Actor)) -> std::cmp::Ordering {
+        a.0.cmp(&b.0).then(b.1.cmp(&a.1))
+    }
+
+    /// Whoever goes next. The last actor handed out goes back in line with
+    /// whatever energy it has left, and anyone who has since died or gone
+    /// to sleep is skipped. `None` means nobody can act until energy is
+    /// handed out again.
+    pub fn next_actor(&mut self, ecs: &World) -> Option<Actor> {
+        if let Some((entity, actor)) = self.acting.take() {
+            if let Some(energy) = ready_energy(ecs, entity) {
+                let entry = (energy, entity, actor);
+                let idx = match self.ready.binary_search_by(|e| Self::order(e, &entry)) {
+                    Ok(idx) | Err(idx) => idx,
+                };
+                self.ready.insert(idx, entry);
+            }
+        }
+        while let Some((_, entity, actor)) = self.ready.pop() {
+            if ready_energy(ecs, entity).is_some() {
+                self.acting = Some((entity, actor));
+                return Some(actor);
+            }
+        }
+        None
+    }
+}
+
+/// How much energy an actor has, if it's still around and can act.
+fn ready_energy(ecs: &World, entity: Entity) -> Option<i32> {
+    let er = ecs.entry_ref(entity).ok()?;
+    if er.get_component::<Active>().is_err() && er.get_component::<Player>().is_err() {
+        return None;
+    }
+    er.get_component::<Energy>()
+        .ok()
+        .filter(|energy| energy.ready())
+        .map(|energy| energy.energy)
+}
+
+/// Everything awake gains energy by its speed. Stunned actors gain nothing,
+/// slowed ones half as much and hasted ones twice as much. Runs once per turn.
+pub fn regain_energy(ecs: &mut World) {
+    <(&mut Energy, Option<&StatusEffects>)>::query()
+        .filter(component::<Active>() | component::<Player>())
+        .for_each_mut(ecs, |(energy, effects)| {
+            let speed = effects.map_or(energy.speed, |e| e.speed(energy.speed));
+            energy.energy = i32::min(MAX_ENERGY, energy.energy + speed);
+        });
+}
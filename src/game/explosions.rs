@@ -33,6 +33,13 @@ pub fn process_explosions(ecs: &mut World, map: &mut Map) {
 
     let mut ignore_me = None;
     damage_tiles.iter().for_each(|pt| {
+        // Doors take the blast on the tile itself, like a shot would
+        let layer = map.current_layer as u32;
+        let idx = map.get_current().point2d_to_index(*pt);
+        if map.get_current().is_door[idx] {
+            crate::game::combat::hit_door(ecs, map, *pt, layer, &mut 6, &mut commands);
+            return;
+        }
         crate::game::combat::hit_tile_contents(
             ecs,
             *pt,
@@ -40,6 +47,17 @@ pub fn process_explosions(ecs: &mut World, map: &mut Map) {
             &mut commands,
             &mut ignore_me,
             6,
+            DamageType::Explosive,
+        );
+        // Anything that survives the blast is left burning
+        crate::game::status_effects::afflict_tile(
+            ecs,
+            *pt,
+            map.current_layer as u32,
+            StatusEffect {
+                kind: StatusKind::Burning,
+                turns: 2,
+            },
         );
     });
 
@@ -0,0 +1,205 @@
+use crate::components::*;
+use crate::map::{layerbuilder, Map};
+use crate::NewState;
+use bracket_lib::prelude::*;
+use legion::*;
+
+/// How far SecBot can throw a grenade
+pub const THROW_RANGE: f32 = 8.0;
+
+fn player(ecs: &World) -> (Entity, Position) {
+    <(Entity, &Player, &Position)>::query()
+        .iter(ecs)
+        .map(|(e, _, pos)| (*e, *pos))
+        .nth(0)
+        .unwrap()
+}
+
+fn take_item(ecs: &mut World, player: Entity, index: usize) -> Option<Item> {
+    let mut entry = ecs.entry_mut(player).ok()?;
+    let inventory = entry.get_component_mut::<Inventory>().ok()?;
+    if index < inventory.items.len() {
+        Some(inventory.items.remove(index))
+    } else {
+        None
+    }
+}
+
+/// Picks up the first item lying under SecBot.
+pub fn pick_up(ecs: &mut World, _map: &mut Map) -> NewState {
+    let (player, player_pos) = player(ecs);
+    let found = <(Entity, &Item, &Position)>::query()
+        .iter(ecs)
+        .filter(|(_, _, pos)| **pos == player_pos)
+        .map(|(e, item, _)| (*e, item.clone()))
+        .nth(0);
+
+    if let Some((entity, item)) = found {
+        ecs.remove(entity);
+        if let Ok(mut entry) = ecs.entry_mut(player) {
+            if let Ok(inventory) = entry.get_component_mut::<Inventory>() {
+                inventory.items.push(item);
+            }
+        }
+        NewState::Player
+    } else {
+        NewState::Wait
+    }
+}
+
+pub fn drop_item(ecs: &mut World, _map: &mut Map, index: usize) -> NewState {
+    let (player, player_pos) = player(ecs);
+    if let Some(item) = take_item(ecs, player, index) {
+        layerbuilder::spawn_item(ecs, &item.id, player_pos.pt, player_pos.layer);
+        NewState::Player
+    } else {
+        NewState::Wait
+    }
+}
+
+/// Uses an item from the inventory. Items that can't do anything right now
+/// stay in the inventory and don't take a turn.
+pub fn use_item(ecs: &mut World, _map: &mut Map, index: usize) -> NewState {
+    let (player, player_pos) = player(ecs);
+    let effect = <(&Player, &Inventory)>::query()
+        .iter(ecs)
+        .find_map(|(_, inventory)| inventory.items.get(index).map(|i| i.effect));
+
+    let used = match effect {
+        Some(ItemEffect::Heal(amount)) => heal(ecs, player, amount),
+        Some(ItemEffect::Ammo(rounds)) => resupply(ecs, player, player_pos, rounds),
+        Some(ItemEffect::Haste(turns)) => {
+            super::status_effects::afflict(
+                ecs,
+                player,
+                StatusEffect {
+                    kind: StatusKind::Hasted,
+                    turns,
+                },
+            );
+            true
+        }
+        // Grenades are thrown, not used
+        Some(ItemEffect::Grenade) | Some(ItemEffect::Keycard) | None => false,
+    };
+
+    if used {
+        take_item(ecs, player, index);
+        NewState::Player
+    } else {
+        NewState::Wait
+    }
+}
+
+fn heal(ecs: &mut World, player: Entity, amount: i32) -> bool {
+    if let Ok(mut entry) = ecs.entry_mut(player) {
+        if let Ok(hp) = entry.get_component_mut::<Health>() {
+            if hp.current < hp.max {
+                hp.current = i32::min(hp.max, hp.current + amount);
+                return true;
+            }
+        }
+    }
+    false
+}
+
+fn add_rounds(ecs: &mut World, entity: Entity, rounds: i32) -> bool {
+    if let Ok(mut entry) = ecs.entry_mut(entity) {
+        if let Ok(loadout) = entry.get_component_mut::<Loadout>() {
+            return loadout.active_mut().resupply(rounds);
+        }
+    }
+    false
+}
+
+// Ammo goes to the targeted marine if they're close enough to hand it over,
+// otherwise into SecBot's own gun.
+fn resupply(ecs: &mut World, player: Entity, player_pos: Position, rounds: i32) -> bool {
+    let marine = <(&Player, &Targeting)>::query()
+        .iter(ecs)
+        .find_map(|(_, t)| t.current_target)
+        .filter(|target| {
+            ecs.entry_ref(*target).map_or(false, |e| {
+                e.get_component::<Colonist>().is_ok()
+                    && e.get_component::<Position>().map_or(false, |pos| {
+                        pos.layer == player_pos.layer
+                            && DistanceAlg::Pythagoras.distance2d(pos.pt, player_pos.pt) < 1.5
+                    })
+            })
+        });
+
+    if let Some(marine) = marine {
+        if add_rounds(ecs, marine, rounds) {
+            if let Ok(pos) = ecs
+                .entry_ref(marine)
+                .map(|e| *e.get_component::<Position>().unwrap())
+            {
+                ecs.push((
+                    Speech { lifetime: 60 },
+                    Description("Thanks for the ammo!".to_string()),
+                    pos,
+                ));
+            }
+            return true;
+        }
+    }
+    add_rounds(ecs, player, rounds)
+}
+
+pub fn is_throwable(ecs: &World, index: usize) -> bool {
+    <(&Player, &Inventory)>::query()
+        .iter(ecs)
+        .any(|(_, inventory)| {
+            inventory
+                .items
+                .get(index)
+                .map_or(false, |i| i.effect == ItemEffect::Grenade)
+        })
+}
+
+/// Where a grenade thrown at `target` comes to rest: it stops short of
+/// anything solid in the way. Returns `None` if the target is out of range.
+pub fn throw_landing(map: &Map, from: Point, target: Point) -> Option<Point> {
+    if DistanceAlg::Pythagoras.distance2d(from, target) > THROW_RANGE {
+        return None;
+    }
+    let layer = map.get_current();
+    let mut landing = from;
+    for pt in line2d_bresenham(from, target).iter().skip(1) {
+        if !layer.in_bounds(*pt) || layer.tiles[layer.point2d_to_index(*pt)].blocked {
+            break;
+        }
+        landing = *pt;
+    }
+    Some(landing)
+}
+
+/// Throws a grenade from the inventory. It lands with the usual fuse, so the
+/// blast goes through the timer and `Boom` pipeline like any other grenade.
+pub fn throw_item(ecs: &mut World, map: &mut Map, index: usize, target: Point) -> NewState {
+    let (player, player_pos) = player(ecs);
+    if !is_throwable(ecs, index) {
+        return NewState::Wait;
+    }
+    let landing = match throw_landing(map, player_pos.pt, target) {
+        Some(landing) if landing != player_pos.pt => landing,
+        _ => return NewState::Wait,
+    };
+
+    take_item(ecs, player, index);
+    ecs.push((
+        Projectile {
+            path: line2d_bresenham(player_pos.pt, landing),
+            layer: player_pos.layer as usize,
+        },
+        Glyph {
+            glyph: to_cp437('g'),
+            color: ColorPair::new(ORANGE, BLACK),
+        },
+    ));
+    let mut rng_lock = crate::RNG.lock();
+    let rng = rng_lock.as_mut().unwrap();
+    layerbuilder::spawn_active_prop(ecs, "live_grenade", landing, player_pos.layer, rng);
+
+    NewState::Player
+}
@@ -1,19 +1,23 @@
 pub mod player;
 pub use player::player_turn;
 pub mod colonists;
-pub use colonists::colonists_turn;
+pub use colonists::colonist_turn;
 pub mod monsters;
-pub use monsters::monsters_turn;
+pub use monsters::monster_turn;
 pub mod combat;
+pub mod energy;
+pub mod targets;
 pub mod utils;
 pub use utils::*;
 pub mod dialog;
 pub use dialog::*;
 pub mod explosions;
 pub use explosions::*;
+pub mod status_effects;
 pub mod timed_events;
 pub use timed_events::*;
 pub mod turn_check;
 pub use turn_check::*;
 pub mod friendly;
+pub mod inventory;
 pub use friendly::*;
@@ -1,91 +1,361 @@
+use super::energy::{FIRE_COST, MELEE_COST, MOVE_COST};
+use super::targets::Targets;
 use crate::components::*;
 use crate::map::Map;
 use bracket_lib::prelude::*;
 use legion::{systems::CommandBuffer, *};
+use std::collections::HashSet;
 
-pub fn monsters_turn(ecs: &mut World, map: &mut Map) {
-    let mut targets: Vec<(Position, Entity)> = <(Entity, &Colonist, &Health, &Position)>::query()
-        .iter(ecs)
-        .map(|(e, _, _, pos)| (*pos, *e))
+/// How close something has to come before an ambusher springs
+const AMBUSH_RANGE: f32 = 2.5;
+
+/// Who a monster has picked to go after this turn.
+#[derive(Clone, Copy)]
+struct Quarry {
+    pt: Point,
+    distance: f32,
+    entity: Entity,
+    visible: bool,
+    /// It has been hurting the monster
+    provoked: bool,
+}
+
+/// A monster takes one action.
+pub fn monster_turn(ecs: &mut World, map: &mut Map, actor: Entity, targets: &Targets) {
+    // Only the acting monster is looked up, so a turn costs the same however
+    // many monsters there are.
+    let (pos, mut hostile, mut behaviour, radius, hp, grudge) = {
+        let er = match ecs.entry_ref(actor) {
+            Ok(er) => er,
+            Err(_) => return,
+        };
+        let (pos, hostile, behaviour, fov) = match (
+            er.get_component::<Position>(),
+            er.get_component::<Hostile>(),
+            er.get_component::<Behaviour>(),
+            er.get_component::<FieldOfView>(),
+        ) {
+            (Ok(pos), Ok(hostile), Ok(behaviour), Ok(fov)) => (pos, hostile, behaviour, fov),
+            _ => return,
+        };
+        let radius = er
+            .get_component::<StatusEffects>()
+            .map_or(fov.radius, |e| e.fov_radius(fov.radius));
+        // Health as a percentage, for deciding when to run away
+        let hp = er
+            .get_component::<Health>()
+            .map_or(100, |hp| hp.current * 100 / i32::max(1, hp.max));
+        let grudge = er.get_component::<Grudge>().ok().cloned();
+        (*pos, hostile.clone(), behaviour.clone(), radius, hp, grudge)
+    };
+    let ppos = match Targets::locate(ecs, targets.player) {
+        Some(ppos) => ppos,
+        None => return,
+    };
+
+    // What can I see? Anything that has been shooting at me counts, even if
+    // it's out of sight. Line of sight is worked out on the monster's own
+    // layer, and only if there's anyone on it to look at.
+    let nearby: Vec<(Position, Entity)> = targets
+        .prey
+        .iter()
+        .filter_map(|t| Targets::locate(ecs, *t).map(|tpos| (tpos, *t)))
+        .filter(|t| t.0.layer == pos.layer)
         .collect();
-    let targets2: Vec<(Position, Entity)> = <(Entity, &Friendly, &Health, &Position)>::query()
-        .iter(ecs)
-        .map(|(e, _, _, pos)| (*pos, *e))
+    let visible_tiles = if nearby.is_empty() {
+        HashSet::new()
+    } else {
+        field_of_view_set(pos.pt, radius, map.get_layer(pos.layer as usize))
+    };
+    let seen: Vec<(Point, f32, Entity, bool, i32)> = nearby
+        .iter()
+        .map(|t| {
+            let distance = DistanceAlg::Pythagoras.distance2d(pos.pt, t.0.pt);
+            let visible = visible_tiles.contains(&t.0.pt);
+            let grudge = grudge.as_ref().map_or(0, |g| g.against(t.1));
+            (t.0.pt, distance, t.1, visible, grudge)
+        })
+        .filter(|t| t.3 || t.4 > 0)
         .collect();
-    targets2.iter().for_each(|t| targets.push(*t));
 
-    let ppos = <(Entity, &Player, &Position)>::query()
-        .iter(ecs)
-        .map(|(e, _, pos)| (*pos, *e))
-        .nth(0)
-        .unwrap();
-    targets.push(ppos.clone());
+    // Who do I go after, and who do I run from?
+    let mut scored: Vec<(f32, Quarry)> = seen
+        .iter()
+        .map(|(pt, distance, target, visible, grudge)| {
+            (
+                super::threat_score(
+                    *target,
+                    ecs,
+                    *distance,
+                    *visible,
+                    behaviour.prefers,
+                    *grudge,
+                ),
+                Quarry {
+                    pt: *pt,
+                    distance: *distance,
+                    entity: *target,
+                    visible: *visible,
+                    provoked: *grudge > 0,
+                },
+            )
+        })
+        .collect();
+    scored.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+    let prey = scored.first().map(|(_, q)| *q);
+    scored.sort_by(|a, b| a.1.distance.partial_cmp(&b.1.distance).unwrap());
+    let threat = scored.iter().find(|(_, q)| q.visible).map(|(_, q)| *q);
+
+    let mut rng_lock = crate::RNG.lock();
+    let rng = rng_lock.as_mut().unwrap();
 
     let mut commands = CommandBuffer::new(ecs);
-    let mut melee_buffer = Vec::<(Entity, Entity, i32)>::new();
-    let mut ranged_buffer = Vec::<(Entity, Entity, i32)>::new();
-    <(Entity, &Active, &Hostile, &Position, &mut FieldOfView)>::query()
-        .iter_mut(ecs)
-        .for_each(|(entity, _, hostile, pos, fov)| {
-            let mut attacked = false;
-            // What can I see?
-            fov.visible_tiles =
-                field_of_view_set(pos.pt, fov.radius, map.get_layer(pos.layer as usize));
-            let mut target_subset: Vec<(Point, f32, Entity)> = targets
-                .iter()
-                .filter(|p| p.0.layer == pos.layer)
-                .map(|p| {
-                    (
-                        p.0.pt,
-                        DistanceAlg::Pythagoras.distance2d(pos.pt, p.0.pt),
-                        p.1,
-                    )
-                })
-                .collect();
-            target_subset.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
-
-            if !target_subset.is_empty() {
-                // Can I melee?
-                // If so, is there anything within reach?
-                if target_subset[0].1 < 1.6 && !hostile.melee.is_empty() {
-                    attacked = true;
-                    hostile.melee.iter().for_each(|m| {
-                        melee_buffer.push((*entity, target_subset[0].2, m.damage));
-                    });
-                }
+    let mut melee_buffer = Vec::<(Entity, Entity, Melee)>::new();
+    let mut ranged_buffer = Vec::<(Entity, Entity, Ranged)>::new();
+    let mut cost = MOVE_COST;
+    let mut destination = None;
 
-                // Can I shoot?
-                // If so, is there anything in range?
-                hostile.ranged.iter().for_each(|ranged| {
-                    attacked = true;
-                    ranged_buffer.push((*entity, target_subset[0].2, ranged.power));
-                });
-            }
+    // Pick a state
+    if hp < behaviour.flee_below {
+        behaviour.state = AiState::Flee;
+    } else if let Some(prey) = prey {
+        if behaviour.state != AiState::Ambush || prey.provoked || prey.distance < AMBUSH_RANGE {
+            behaviour.state = AiState::Hunt;
+            behaviour.last_seen = Some(prey.pt);
+        }
+    }
 
-            // Movement
-            if !attacked {
-                // What's my aggro target?
-                if ppos.0.layer != pos.layer {
-                    // TODO: Path a staircase! For now, sit there.
+    // Act on it
+    match behaviour.state {
+        AiState::Hunt => {
+            if let Some(prey) = prey {
+                let backing_off = if prey.distance < behaviour.keep_distance as f32 {
+                    destination = step_away(map, &pos, prey.pt);
+                    destination.is_some()
+                } else {
+                    false
+                };
+                if !backing_off {
+                    // Can't shoot what it can't see, so close in instead
+                    let attack_cost = if prey.visible {
+                        attack(
+                            &hostile,
+                            actor,
+                            &prey,
+                            &mut melee_buffer,
+                            &mut ranged_buffer,
+                        )
+                    } else {
+                        None
+                    };
+                    if let Some(attack_cost) = attack_cost {
+                        cost = attack_cost;
+                    } else {
+                        destination =
+                            step_towards(map, &pos, prey.pt).filter(|next| *next != prey.pt);
+                    }
+                }
+            } else if ppos.layer != pos.layer {
+                // Lost the player off-layer, so follow them down (or up) the
+                // stairs
+                follow_stairs(map, &mut hostile, &pos, &ppos, &mut commands, actor);
+            } else if let Some(last_seen) = behaviour.last_seen {
+                // Go and look where it was last seen
+                if pos.pt == last_seen {
+                    behaviour.last_seen = None;
+                    behaviour.state = behaviour.default;
                 } else {
-                    // Path at the player
-                    let start = map.get_current().point2d_to_index(pos.pt);
-                    let end = map.get_current().point2d_to_index(ppos.0.pt);
-                    let path = a_star_search(start, end, map.get_layer(pos.layer as usize));
-                    if path.success && path.steps.len() > 2 {
-                        let next = map.get_current().index_to_point2d(path.steps[1]);
-                        commands.add_component(*entity, Position::with_pt(next, pos.layer));
+                    destination = step_towards(map, &pos, last_seen);
+                }
+            } else {
+                behaviour.state = behaviour.default;
+            }
+        }
+        AiState::Flee => {
+            if let Some(threat) = threat {
+                destination = step_away(map, &pos, threat.pt);
+                // Cornered, so fight
+                if destination.is_none() {
+                    if let Some(attack_cost) = attack(
+                        &hostile,
+                        actor,
+                        &threat,
+                        &mut melee_buffer,
+                        &mut ranged_buffer,
+                    ) {
+                        cost = attack_cost;
                     }
                 }
             }
-        });
+        }
+        AiState::Wander => {
+            let delta = Point::new(rng.range(-1, 2), rng.range(-1, 2));
+            if can_step(map, &pos, delta) {
+                destination = Some(pos.pt + delta);
+            }
+        }
+        AiState::Guard => {
+            if pos.pt != behaviour.home {
+                destination = step_towards(map, &pos, behaviour.home);
+            }
+        }
+        AiState::Idle | AiState::Ambush => {}
+    }
+
+    // Only hunters following the stairs keep a route
+    if behaviour.state != AiState::Hunt || prey.is_some() || ppos.layer == pos.layer {
+        hostile.path = None;
+    }
+    if let Some(next) = destination {
+        commands.add_component(actor, Position::with_pt(next, pos.layer));
+    }
+    drop(rng_lock);
+
+    if let Some(mut entry) = ecs.entry(actor) {
+        if let Ok(h) = entry.get_component_mut::<Hostile>() {
+            *h = hostile;
+        }
+        if let Ok(b) = entry.get_component_mut::<Behaviour>() {
+            *b = behaviour;
+        }
+        if let Ok(fov) = entry.get_component_mut::<FieldOfView>() {
+            fov.visible_tiles = visible_tiles;
+        }
+    }
     commands.flush(ecs);
+    super::energy::spend(ecs, actor, cost);
 
     // Perform combat
-    melee_buffer.iter().for_each(|(a, d, dmg)| {
-        super::combat::melee(ecs, map, *a, *d, *dmg);
+    melee_buffer.iter().for_each(|(a, d, attack)| {
+        super::combat::melee(ecs, map, *a, *d, attack);
     });
-    ranged_buffer.iter().for_each(|(a, d, dmg)| {
-        super::combat::ranged_attack(ecs, map, *a, *d, *dmg);
+    ranged_buffer.iter().for_each(|(a, d, attack)| {
+        super::combat::ranged_attack(ecs, map, *a, *d, attack);
     });
 }
+
+/// Queues up every attack that can reach the target, returning what it cost.
+fn attack(
+    hostile: &Hostile,
+    attacker: Entity,
+    target: &Quarry,
+    melee_buffer: &mut Vec<(Entity, Entity, Melee)>,
+    ranged_buffer: &mut Vec<(Entity, Entity, Ranged)>,
+) -> Option<i32> {
+    let mut cost = None;
+    if target.distance < 1.6 {
+        hostile.melee.iter().for_each(|m| {
+            melee_buffer.push((attacker, target.entity, m.clone()));
+            cost = Some(MELEE_COST);
+        });
+    }
+    hostile
+        .ranged
+        .iter()
+        .filter(|r| target.distance <= r.range as f32)
+        .for_each(|r| {
+            ranged_buffer.push((attacker, target.entity, r.clone()));
+            cost = Some(FIRE_COST);
+        });
+    cost
+}
+
+/// The next step along the shortest path to `to`, if there is one.
+fn step_towards(map: &Map, pos: &Position, to: Point) -> Option<Point> {
+    let layer = map.get_layer(pos.layer as usize);
+    let start = layer.point2d_to_index(pos.pt);
+    let end = layer.point2d_to_index(to);
+    let path = a_star_search(start, end, layer);
+    if path.success && path.steps.len() > 1 {
+        Some(layer.index_to_point2d(path.steps[1]))
+    } else {
+        None
+    }
+}
+
+/// The neighbouring tile that gets furthest from `threat`, if any of them
+/// are further away than where it's standing now.
+fn step_away(map: &Map, pos: &Position, threat: Point) -> Option<Point> {
+    let mut best = None;
+    let mut best_distance = DistanceAlg::Pythagoras.distance2d(pos.pt, threat);
+    for y in -1..=1 {
+        for x in -1..=1 {
+            let next = pos.pt + Point::new(x, y);
+            if can_step(map, pos, Point::new(x, y)) {
+                let distance = DistanceAlg::Pythagoras.distance2d(next, threat);
+                if distance > best_distance {
+                    best = Some(next);
+                    best_distance = distance;
+                }
+            }
+        }
+    }
+    best
+}
+
+/// Can it move one tile by `delta`? Unlike pathing, closed doors count as
+/// walls.
+fn can_step(map: &Map, pos: &Position, delta: Point) -> bool {
+    let layer = map.get_layer(pos.layer as usize);
+    layer.is_exit_possible(pos.pt, delta)
+        && !layer.tiles[layer.point2d_to_index(pos.pt + delta)].blocked
+}
+
+/// Walks a monster to the stairs towards the player's layer, and through
+/// them once it gets there.
+fn follow_stairs(
+    map: &Map,
+    hostile: &mut Hostile,
+    pos: &Position,
+    player_pos: &Position,
+    commands: &mut CommandBuffer,
+    entity: Entity,
+) {
+    let (stairs, arrival) = stairs_towards(map, pos.layer, player_pos.layer);
+    let layer = map.get_layer(pos.layer as usize);
+    if pos.pt == stairs {
+        // Don't land on top of the player
+        if arrival != *player_pos {
+            commands.add_component(entity, arrival);
+            hostile.path = None;
+        }
+        return;
+    }
+
+    // The stairs don't move, so the route is worked out once
+    let stairs_idx = layer.point2d_to_index(stairs);
+    if hostile.path.as_ref().and_then(|p| p.last()) != Some(&stairs_idx) {
+        let start = layer.point2d_to_index(pos.pt);
+        let path = a_star_search(start, stairs_idx, layer);
+        hostile.path = if path.success {
+            Some(path.steps.into_iter().skip(1).collect())
+        } else {
+            None
+        };
+    }
+    if let Some(path) = &mut hostile.path {
+        if !path.is_empty() {
+            let next = layer.index_to_point2d(path.remove(0));
+            commands.add_component(entity, Position::with_pt(next, pos.layer));
+        }
+    }
+}
+
+/// The stairs a monster on `layer` takes towards `target_layer`, and where it
+/// comes out on the other side.
+fn stairs_towards(map: &Map, layer: u32, target_layer: u32) -> (Point, Position) {
+    if target_layer < layer {
+        (
+            map.get_layer(layer as usize).colonist_exit,
+            Position::with_pt(
+                map.get_layer(layer as usize - 1).find_down_stairs(),
+                layer - 1,
+            ),
+        )
+    } else {
+        (
+            map.get_layer(layer as usize).find_down_stairs(),
+            Position::with_pt(map.get_layer(layer as usize + 1).starting_point, layer + 1),
+        )
+    }
+}
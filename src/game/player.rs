@@ -1,30 +1,98 @@
+use super::energy::*;
 use super::{combat::player_open_fire_at_target, targeting_weight};
 use crate::components::*;
+use crate::simulation::{Command, Simulation};
 use crate::{map::Map, map::TileType, NewState};
 use bracket_lib::prelude::*;
 use legion::systems::CommandBuffer;
 use legion::*;
 use std::collections::HashSet;
 
-pub fn player_turn(ctx: &mut BTerm, ecs: &mut World, map: &mut Map) -> NewState {
+pub fn player_turn(ctx: &mut BTerm, sim: &mut Simulation, clicked: Option<Entity>) -> NewState {
     // Check for input
-    let mut new_state = if let Some(key) = ctx.key {
-        match key {
-            VirtualKeyCode::Up | VirtualKeyCode::W => try_move(ecs, map, 0, -1),
-            VirtualKeyCode::Down | VirtualKeyCode::S => try_move(ecs, map, 0, 1),
-            VirtualKeyCode::Left | VirtualKeyCode::A => try_move(ecs, map, -1, 0),
-            VirtualKeyCode::Right | VirtualKeyCode::D => try_move(ecs, map, 1, 0),
-            VirtualKeyCode::T | VirtualKeyCode::Tab => cycle_target(ecs),
-            VirtualKeyCode::Comma => go_up(ecs, map),
-            VirtualKeyCode::Period => go_down(ecs, map),
-            VirtualKeyCode::Space => NewState::Player, // Wait action
-            VirtualKeyCode::F => player_open_fire_at_target(ecs, map),
-            VirtualKeyCode::Slash => NewState::Help,
-            _ => NewState::Wait,
+    if let Some(key) = ctx.key {
+        if key == VirtualKeyCode::Slash {
+            return NewState::Help;
         }
-    } else {
-        NewState::Wait
+        if key == VirtualKeyCode::Escape {
+            return NewState::SaveAndQuit;
+        }
+        if key == VirtualKeyCode::I {
+            return NewState::Inventory;
+        }
+        if key == VirtualKeyCode::X {
+            return NewState::DropMenu;
+        }
+        if let Some(command) = command_for_key(key) {
+            return sim.player_command(command);
+        }
+    }
+
+    // Clicking on something in the target list selects it
+    if let Some(target) = clicked {
+        let index = <(&Player, &Targeting)>::query()
+            .iter(&sim.ecs)
+            .find_map(|(_, t)| t.targets.iter().position(|(e, _)| *e == target));
+        if let Some(index) = index {
+            return sim.player_command(Command::SelectTarget(index));
+        }
+    }
+    NewState::Wait
+}
+
+pub fn command_for_key(key: VirtualKeyCode) -> Option<Command> {
+    match key {
+        VirtualKeyCode::Up | VirtualKeyCode::W => Some(Command::Move {
+            delta_x: 0,
+            delta_y: -1,
+        }),
+        VirtualKeyCode::Down | VirtualKeyCode::S => Some(Command::Move {
+            delta_x: 0,
+            delta_y: 1,
+        }),
+        VirtualKeyCode::Left | VirtualKeyCode::A => Some(Command::Move {
+            delta_x: -1,
+            delta_y: 0,
+        }),
+        VirtualKeyCode::Right | VirtualKeyCode::D => Some(Command::Move {
+            delta_x: 1,
+            delta_y: 0,
+        }),
+        VirtualKeyCode::T | VirtualKeyCode::Tab => Some(Command::CycleTarget),
+        VirtualKeyCode::Q => Some(Command::CycleWeapon),
+        VirtualKeyCode::R => Some(Command::Reload),
+        VirtualKeyCode::G => Some(Command::PickUp),
+        VirtualKeyCode::Comma => Some(Command::GoUp),
+        VirtualKeyCode::Period => Some(Command::GoDown),
+        VirtualKeyCode::Space => Some(Command::Wait),
+        VirtualKeyCode::F => Some(Command::Fire),
+        _ => None,
+    }
+}
+
+pub fn perform_command(command: Command, ecs: &mut World, map: &mut Map) -> NewState {
+    // Each action costs energy, unless it turned out to be free
+    let (mut new_state, cost) = match command {
+        Command::Move { delta_x, delta_y } => try_move(ecs, map, delta_x, delta_y),
+        Command::CycleTarget => (cycle_target(ecs), 0),
+        Command::SelectTarget(index) => (select_target(ecs, index), 0),
+        Command::CycleWeapon => (cycle_weapon(ecs), 0),
+        Command::Reload => (reload(ecs), RELOAD_COST),
+        Command::PickUp => (super::inventory::pick_up(ecs, map), ITEM_COST),
+        Command::UseItem(index) => (super::inventory::use_item(ecs, map, index), ITEM_COST),
+        Command::DropItem(index) => (super::inventory::drop_item(ecs, map, index), ITEM_COST),
+        Command::Throw { item, target } => (
+            super::inventory::throw_item(ecs, map, item, target),
+            THROW_COST,
+        ),
+        Command::GoUp => (go_up(ecs, map), STAIRS_COST),
+        Command::GoDown => (go_down(ecs, map), STAIRS_COST),
+        Command::Wait => (NewState::Player, WAIT_COST),
+        Command::Fire => (player_open_fire_at_target(ecs, map), FIRE_COST),
     };
+    if new_state != NewState::Wait {
+        spend_player(ecs, cost);
+    }
 
     // Check for tile trigger effects
     tile_triggers(&mut new_state, ecs, map);
@@ -34,25 +102,63 @@ pub fn player_turn(ctx: &mut BTerm, ecs: &mut World, map: &mut Map) -> NewState
     new_state
 }
 
-fn try_move(ecs: &mut World, map: &mut Map, delta_x: i32, delta_y: i32) -> NewState {
+/// Returns the new state and the energy the move cost.
+fn try_move(ecs: &mut World, map: &mut Map, delta_x: i32, delta_y: i32) -> (NewState, i32) {
+    // Moving into something hostile or breakable hits it instead
+    let (player, player_pos, melee) = <(Entity, &Player, &Position, &Melee)>::query()
+        .iter(ecs)
+        .map(|(e, _, pos, melee)| (*e, *pos, melee.clone()))
+        .nth(0)
+        .unwrap();
+    let destination = player_pos.pt + Point::new(delta_x, delta_y);
+    let victim = <(Entity, &Position, &Health)>::query()
+        .filter(component::<Hostile>() | component::<SetDecoration>())
+        .iter(ecs)
+        .find(|(_, pos, _)| pos.layer == player_pos.layer && pos.pt == destination)
+        .map(|(e, _, _)| *e);
+    if let Some(victim) = victim {
+        super::combat::melee(ecs, map, player, victim, &melee);
+        return (NewState::Player, MELEE_COST);
+    }
+
+    let has_keycard = <(&Player, &Inventory)>::query()
+        .iter(ecs)
+        .any(|(_, inventory)| inventory.has_keycard());
+    let locked_doors = <(&Position, &Door, &Locked)>::query()
+        .iter(ecs)
+        .filter(|(pos, _, _)| pos.layer == map.current_layer as u32)
+        .map(|(pos, _, _)| pos.pt)
+        .collect::<HashSet<Point>>();
+
     let mut find_player = <(&Player, &mut Position)>::query();
-    let mut result = NewState::Wait;
+    let mut result = (NewState::Wait, 0);
     let mut doors_to_delete = HashSet::new();
+    let mut locked_out = None;
     find_player.iter_mut(ecs).for_each(|(_, pos)| {
         let new_pos = pos.pt + Point::new(delta_x, delta_y);
         let new_idx = map.get_current().point2d_to_index(new_pos);
         if !map.get_current().tiles[new_idx].blocked {
             pos.pt = new_pos;
-            result = NewState::Player;
+            result = (NewState::Player, MOVE_COST);
         } else if map.get_current().is_door[new_idx] {
-            map.get_current_mut().is_door[new_idx] = false;
-            map.get_current_mut().tiles[new_idx].blocked = false;
-            map.get_current_mut().tiles[new_idx].opaque = false;
-            map.get_current_mut().tiles[new_idx].glyph = to_cp437('.');
-            doors_to_delete.insert(map.get_current().index_to_point2d(new_idx));
+            if locked_doors.contains(&new_pos) && !has_keycard {
+                locked_out = Some(Position::with_pt(new_pos, pos.layer));
+            } else {
+                map.get_current_mut().open_door(new_idx);
+                doors_to_delete.insert(map.get_current().index_to_point2d(new_idx));
+                result = (NewState::Player, DOOR_COST);
+            }
         }
     });
 
+    if let Some(pos) = locked_out {
+        ecs.push((
+            Speech { lifetime: 60 },
+            Description("Locked. Needs a keycard.".to_string()),
+            pos,
+        ));
+    }
+
     if !doors_to_delete.is_empty() {
         let mut commands = CommandBuffer::new(ecs);
         let mut q = <(Entity, &Position, &Door)>::query();
@@ -67,6 +173,25 @@ fn try_move(ecs: &mut World, map: &mut Map, delta_x: i32, delta_y: i32) -> NewSt
     result
 }
 
+// Swapping weapons is free; it doesn't use up a turn.
+fn cycle_weapon(ecs: &mut World) -> NewState {
+    <(&Player, &mut Loadout)>::query().for_each_mut(ecs, |(_, loadout)| loadout.cycle());
+    NewState::Wait
+}
+
+fn reload(ecs: &mut World) -> NewState {
+    let player = <(Entity, &Player)>::query()
+        .iter(ecs)
+        .map(|(e, _)| *e)
+        .nth(0)
+        .unwrap();
+    if super::combat::reload_loadout(ecs, player) {
+        NewState::Player
+    } else {
+        NewState::Wait
+    }
+}
+
 fn tile_triggers(new_state: &mut NewState, ecs: &mut World, _map: &mut Map) {
     if *new_state == NewState::Wait {
         return;
@@ -100,11 +225,18 @@ pub fn update_fov(new_state: &NewState, ecs: &mut World, map: &mut Map) {
     let mut player_entity = None;
 
     // Build the player FOV
-    let mut query = <(Entity, &Player, &Position, &mut FieldOfView)>::query();
-    query.for_each_mut(ecs, |(e, _, pos, fov)| {
+    let mut query = <(
+        Entity,
+        &Player,
+        &Position,
+        &mut FieldOfView,
+        Option<&StatusEffects>,
+    )>::query();
+    query.for_each_mut(ecs, |(e, _, pos, fov, effects)| {
         player_pos = pos.pt;
         player_entity = Some(*e);
-        fov.visible_tiles = field_of_view_set(pos.pt, fov.radius, map.get_current());
+        let radius = effects.map_or(fov.radius, |s| s.fov_radius(fov.radius));
+        fov.visible_tiles = field_of_view_set(pos.pt, radius, map.get_current());
         let current_layer = map.get_current_mut();
         current_layer.clear_visible();
         fov.visible_tiles.iter().for_each(|pt| {
@@ -179,11 +311,21 @@ fn cycle_target(ecs: &mut World) -> NewState {
     NewState::Wait
 }
 
+fn select_target(ecs: &mut World, index: usize) -> NewState {
+    <(&Player, &mut Targeting)>::query().for_each_mut(ecs, |(_, targeting)| {
+        if index < targeting.targets.len() {
+            targeting.index = index;
+            targeting.current_target = Some(targeting.targets[index].0);
+        }
+    });
+    NewState::Wait
+}
+
 fn go_up(ecs: &mut World, map: &mut Map) -> NewState {
     let mut find_player = <(&Player, &mut Position)>::query();
     find_player.for_each_mut(ecs, |(_, pos)| {
         let idx = map.get_current().point2d_to_index(pos.pt);
-        if map.get_current().tiles[idx].tile_type == TileType::StairsUp {
+        if pos.layer > 0 && map.get_current().tiles[idx].tile_type == TileType::StairsUp {
             // It really is an up staircase
             let new_layer = pos.layer - 1;
             map.set_current_layer(new_layer as usize);
@@ -211,27 +353,10 @@ fn go_down(ecs: &mut World, map: &mut Map) -> NewState {
 
 // Returns (probability, range)
 pub fn hit_probability(ecs: &World, target: Entity) -> (u32, u32) {
-    let mut target_pos = Point::zero();
-    if let Ok(entry) = ecs.entry_ref(target) {
-        if let Ok(pos) = entry.get_component::<Position>() {
-            target_pos = pos.pt;
-        }
-    }
-
-    let player_pos = <(&Player, &Position)>::query()
+    let player = <(Entity, &Player)>::query()
         .iter(ecs)
-        .map(|(_, pos)| pos)
+        .map(|(e, _)| *e)
         .nth(0)
-        .unwrap()
-        .pt;
-
-    let range = DistanceAlg::Pythagoras.distance2d(player_pos, target_pos) as u32;
-
-    // TODO: More complexity here
-    let mut hit_chance: i32 = 90;
-    if range > 5 {
-        hit_chance -= (range as i32 - 5) * 5;
-    }
-
-    (hit_chance as u32, range)
+        .unwrap();
+    super::combat::hit_probability(ecs, player, target)
 }
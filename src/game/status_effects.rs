@@ -0,0 +1,84 @@
+use crate::components::*;
+use bracket_lib::prelude::*;
+use legion::systems::CommandBuffer;
+use legion::*;
+
+/// Applies a status effect to a single living entity. Doors have health but
+/// aren't alive, so they're left alone.
+pub fn afflict(ecs: &mut World, entity: Entity, effect: StatusEffect) {
+    if let Some(mut entry) = ecs.entry(entity) {
+        if entry.get_component::<Health>().is_err() || entry.get_component::<Door>().is_ok() {
+            return;
+        }
+        if let Ok(effects) = entry.get_component_mut::<StatusEffects>() {
+            effects.add(effect);
+        } else {
+            entry.add_component(StatusEffects(vec![effect]));
+        }
+    }
+}
+
+/// Applies a status effect to everything living on a tile.
+pub fn afflict_tile(ecs: &mut World, pt: Point, layer: u32, effect: StatusEffect) {
+    let victims: Vec<Entity> = <(Entity, &Position, &Health)>::query()
+        .filter(!component::<Door>())
+        .iter(ecs)
+        .filter(|(_, pos, _)| pos.layer == layer && pos.pt == pt)
+        .map(|(e, _, _)| *e)
+        .collect();
+    victims.iter().for_each(|e| afflict(ecs, *e, effect));
+}
+
+pub fn fov_radius(ecs: &World, entity: Entity, radius: i32) -> i32 {
+    ecs.entry_ref(entity)
+        .ok()
+        .and_then(|e| {
+            e.get_component::<StatusEffects>()
+                .ok()
+                .map(|s| s.fov_radius(radius))
+        })
+        .unwrap_or(radius)
+}
+
+/// Explains why SecBot is sitting out a turn, if a status effect is to blame.
+pub fn announce_lost_turn(ecs: &mut World) {
+    let why = <(&Player, &Position, &StatusEffects)>::query()
+        .iter(ecs)
+        .find_map(|(_, pos, s)| {
+            if s.has(StatusKind::Stunned) {
+                Some((*pos, "Systems rebooting..."))
+            } else if s.has(StatusKind::Slowed) {
+                Some((*pos, "Actuators sluggish..."))
+            } else {
+                None
+            }
+        });
+    if let Some((pos, why)) = why {
+        ecs.push((Speech { lifetime: 40 }, Description(why.to_string()), pos));
+    }
+}
+
+/// Counts effects down and deals damage over time. Runs once per turn.
+pub fn tick_status_effects(ecs: &mut World) {
+    let mut commands = CommandBuffer::new(ecs);
+    let mut damage = Vec::new();
+    <(Entity, &mut StatusEffects)>::query().for_each_mut(ecs, |(entity, effects)| {
+        effects.0.iter_mut().for_each(|e| {
+            match e.kind {
+                StatusKind::Burning => damage.push((*entity, DamageType::Fire)),
+                StatusKind::Corroded => damage.push((*entity, DamageType::Acid)),
+                _ => {}
+            }
+            e.turns -= 1;
+        });
+        effects.0.retain(|e| e.turns > 0);
+        if effects.0.is_empty() {
+            commands.remove_component::<StatusEffects>(*entity);
+        }
+    });
+    commands.flush(ecs);
+
+    damage.iter().for_each(|(entity, damage_type)| {
+        super::combat::damage_entity(ecs, *entity, 1, *damage_type);
+    });
+}
@@ -0,0 +1,53 @@
+use crate::components::*;
+use legion::*;
+
+/// Who can be fought, gathered once per enemy turn rather than by every
+/// actor. Actors move and die during the turn, so positions are looked up
+/// when they're needed.
+pub struct Targets {
+    /// Colonists, friendly bots and SecBot: what monsters go after
+    pub prey: Vec<Entity>,
+    /// Awake monsters: what colonists and bots shoot at
+    pub hostiles: Vec<Entity>,
+    pub player: Entity,
+}
+
+impl Targets {
+    pub fn gather(ecs: &World) -> Self {
+        let mut prey: Vec<Entity> = <(Entity, &Colonist, &Health)>::query()
+            .iter(ecs)
+            .map(|(e, _, _)| *e)
+            .collect();
+        <(Entity, &Friendly, &Health)>::query()
+            .iter(ecs)
+            .for_each(|(e, _, _)| prey.push(*e));
+        let player = <(Entity, &Player)>::query()
+            .iter(ecs)
+            .map(|(e, _)| *e)
+            .nth(0)
+            .unwrap();
+        prey.push(player);
+
+        let hostiles = <(Entity, &Hostile, &Active, &Health)>::query()
+            .iter(ecs)
+            .map(|(e, _, _, _)| *e)
+            .collect();
+
+        Self {
+            prey,
+            hostiles,
+            player,
+        }
+    }
+
+    /// Where a target is now, or `None` if it has since been removed.
+    pub fn locate(ecs: &World, target: Entity) -> Option<Position> {
+        ecs.entry_ref(target).ok().and_then(|er| {
+            if er.get_component::<Health>().is_ok() {
+                er.get_component::<Position>().ok().copied()
+            } else {
+                None
+            }
+        })
+    }
+}
@@ -3,6 +3,9 @@ use crate::map::Map;
 use legion::systems::CommandBuffer;
 use legion::*;
 
+/// Blast radius of a timed explosion, such as a grenade
+pub const BOOM_RANGE: i32 = 3;
+
 pub fn manage_event_timers(ecs: &mut World, _map: &Map) {
     let mut commands = CommandBuffer::new(ecs);
     let mut spawn_list: Vec<Position> = Vec::new();
@@ -18,7 +21,10 @@ pub fn manage_event_timers(ecs: &mut World, _map: &Map) {
                 // Create an explosion (TODO: Conditional if we need more timers)
                 match timer.event {
                     EventType::Boom => {
-                        commands.push((Position::with_pt(pos.pt, pos.layer), Boom { range: 3 }));
+                        commands.push((
+                            Position::with_pt(pos.pt, pos.layer),
+                            Boom { range: BOOM_RANGE },
+                        ));
                     }
                     EventType::HatchXenomorph => {
                         spawn_list.push(pos.clone());
@@ -34,7 +40,7 @@ pub fn manage_event_timers(ecs: &mut World, _map: &Map) {
         });
 
     spawn_list.iter().for_each(|pos| {
-        crate::map::layerbuilder::spawn_xenomorph(ecs, pos.pt, pos.layer);
+        crate::map::layerbuilder::spawn_monster(ecs, "Xenomorph", pos.pt, pos.layer);
     });
 
     commands.flush(ecs);
@@ -24,3 +24,37 @@ pub fn targeting_weight(entity: Entity, ecs: &World) -> f32 {
     }
     weight
 }
+
+/// How keen a hostile is to go after `target`: lower is keener. Starts from
+/// the distance, like the player's `targeting_weight`, then favours targets in
+/// plain sight, defenceless colonists, whatever the monster prefers and
+/// whoever has been hurting it.
+pub fn threat_score(
+    target: Entity,
+    ecs: &World,
+    distance: f32,
+    visible: bool,
+    prefers: Prey,
+    grudge: i32,
+) -> f32 {
+    let mut score = distance;
+    if !visible {
+        score += 10.0;
+    }
+    if has_component::<Colonist>(target, ecs) {
+        score -= 2.0;
+        if !has_component::<Loadout>(target, ecs) {
+            score -= 2.0;
+        }
+        if prefers == Prey::Colonists {
+            score -= 10.0;
+        }
+    } else if has_component::<Player>(target, ecs) {
+        if prefers == Prey::SecBot {
+            score -= 10.0;
+        }
+    } else if has_component::<Friendly>(target, ecs) {
+        score += 1.0;
+    }
+    score - grudge as f32 * 2.0
+}
@@ -8,7 +8,11 @@ pub use rng::RNG; // Make the RNG globally available
 mod components;
 pub mod game;
 mod map;
+mod raws;
 mod render;
+mod replay;
+mod save;
+mod simulation;
 mod text;
 mod turn;
 pub use turn::NewState;
@@ -27,6 +31,43 @@ embedded_resource!(DEAD_SKULL, "../resources/skull.xp");
 embedded_resource!(ESCAPED, "../resources/takeoff.xp");
 
 fn main() -> BError {
+    // `--seed <n>` replays a specific world, `--headless <turns>` runs the
+    // simulation without opening a window. `--replay <file>` plays back a
+    // recording, either every `--replay-speed <frames>` or one command per
+    // key press with `--replay-step`.
+    let args: Vec<String> = std::env::args().collect();
+    let seed = arg_value(&args, "--seed").and_then(|n| n.parse::<u64>().ok());
+    let headless = args.iter().any(|a| a == "--headless");
+    let recording = match arg_value(&args, "--replay") {
+        Some(path) => Some(replay::Recording::load(path).map_err(|e| e.to_string())?),
+        None => None,
+    };
+    let replay = recording.map(|recording| {
+        let speed = if args.iter().any(|a| a == "--replay-step") {
+            replay::ReplaySpeed::Step
+        } else {
+            replay::ReplaySpeed::FramesPerCommand(
+                arg_value(&args, "--replay-speed")
+                    .and_then(|n| n.parse::<u32>().ok())
+                    .unwrap_or(10),
+            )
+        };
+        replay::Replay::new(recording, speed)
+    });
+    if headless {
+        if let Some(mut replay) = replay {
+            let mut sim = simulation::Simulation::new(replay.seed());
+            replay.run_headless(&mut sim);
+            print_summary(replay.seed());
+        } else {
+            let turns = arg_value(&args, "--headless")
+                .and_then(|n| n.parse::<usize>().ok())
+                .unwrap_or(100);
+            run_headless(seed.unwrap_or_else(rng::random_seed), turns);
+        }
+        return Ok(());
+    }
+
     link_resource!(TILE_FONT, "resources/font.png");
     link_resource!(VGA_FONT, "resources/vga.png");
     link_resource!(DEAD_SKULL, "resources/skull.xp");
@@ -46,5 +87,34 @@ fn main() -> BError {
         .with_sparse_console(112, 31, "vga.png") // Console 4: User Interface
         .build()?;
 
-    main_loop(context, State::new())
+    main_loop(context, State::new(seed, replay))
+}
+
+fn arg_value<'a>(args: &'a [String], flag: &str) -> Option<&'a String> {
+    args.iter()
+        .position(|a| a == flag)
+        .and_then(|i| args.get(i + 1))
+}
+
+fn run_headless(seed: u64, turns: usize) {
+    let mut sim = simulation::Simulation::new(seed);
+    for _ in 0..turns {
+        if sim.is_game_over() {
+            break;
+        }
+        sim.play_turn(simulation::Command::Wait);
+    }
+    print_summary(seed);
+}
+
+fn print_summary(seed: u64) {
+    let stats = stats::get_stats();
+    println!(
+        "Seed: {}, Turns: {}, Dead: {}, Props smashed: {}, Hostiles killed: {}",
+        seed,
+        stats.turns_elapsed,
+        stats.total_dead,
+        stats.total_props_smashed,
+        stats.total_hostiles_killed
+    );
 }
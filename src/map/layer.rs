@@ -1,7 +1,8 @@
-use super::{layerbuilder::*, Tile, TileType, HEIGHT, TILES, WIDTH};
+use super::{Tile, TileType, HEIGHT, TILES, WIDTH};
 use bracket_lib::prelude::*;
-use legion::*;
+use serde::{Deserialize, Serialize};
 
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Layer {
     pub tiles: Vec<Tile>,
     pub revealed: Vec<bool>,
@@ -12,22 +13,16 @@ pub struct Layer {
 }
 
 impl Layer {
-    pub fn new(depth: usize, ecs: &mut World) -> Self {
-        let layer = match depth {
-            0 => build_entrance(ecs),
-            1 => build_mine_top(ecs),
-            2 => build_mine_middle(ecs),
-            3 => build_caverns(ecs),
-            _ => Self {
-                tiles: vec![Tile::default(); TILES],
-                starting_point: Point::new(WIDTH / 2, HEIGHT / 2),
-                visible: vec![false; TILES],
-                revealed: vec![false; TILES],
-                is_door: vec![false; TILES],
-                colonist_exit: Point::zero(),
-            },
-        };
-        layer
+    /// An empty layer, for builders to work on.
+    pub fn new() -> Self {
+        Self {
+            tiles: vec![Tile::default(); TILES],
+            starting_point: Point::new(WIDTH / 2, HEIGHT / 2),
+            visible: vec![false; TILES],
+            revealed: vec![false; TILES],
+            is_door: vec![false; TILES],
+            colonist_exit: Point::zero(),
+        }
     }
 
     pub fn render(&self, ctx: &mut BTerm) {
@@ -73,6 +68,15 @@ impl Layer {
         false
     }
 
+    /// Turns a closed door tile into an open one. The caller removes the
+    /// door entity.
+    pub fn open_door(&mut self, idx: usize) {
+        self.is_door[idx] = false;
+        self.tiles[idx].blocked = false;
+        self.tiles[idx].opaque = false;
+        self.tiles[idx].glyph = to_cp437('.');
+    }
+
     pub fn find_down_stairs(&self) -> Point {
         let idx = self
             .tiles
@@ -1,12 +1,25 @@
 use super::edge_filler_lava;
-use super::{colonists::*, monsters::*, props::*};
+use super::{
+    colonists::*, monsters::*, spawn_table::spawn_from_table, vaults::place_vaults, LayerBuilder,
+};
+use crate::components::{Name, Position};
 use crate::map::{tile::TileType, Layer, Tile, HEIGHT, WIDTH};
 use bracket_lib::prelude::*;
 use legion::*;
 
-pub fn build_caverns(ecs: &mut World) -> Layer {
-    let mut layer = Layer::new(std::usize::MAX, ecs); // Gets a default layer
-                                                      // We're using Cellular Automata here, straight out of Hands-On Rust.
+pub struct Caverns {
+    pub queen: bool,
+}
+
+impl LayerBuilder for Caverns {
+    fn build(&self, ecs: &mut World, depth: u32) -> Layer {
+        build_caverns(ecs, depth, self.queen)
+    }
+}
+
+fn build_caverns(ecs: &mut World, depth: u32, queen: bool) -> Layer {
+    // We're using Cellular Automata here, straight out of Hands-On Rust.
+    let mut layer = Layer::new();
     random_noise_map(&mut layer);
     for _ in 0..15 {
         iteration(&mut layer);
@@ -47,52 +60,47 @@ pub fn build_caverns(ecs: &mut World) -> Layer {
         }
     });
 
-    // Spawn the queen far away
-    let farthest_tile = layer.index_to_point2d(
-        dm.map
-            .iter()
-            .enumerate()
-            .filter(|(i, _)| layer.tiles[*i].tile_type == TileType::Floor)
-            .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap())
-            .unwrap()
-            .0,
-    );
-    spawn_queen(ecs, farthest_tile, 3);
-
-    // Spawn the other SecBot near the entrance
-    let secbot_tile = layer.index_to_point2d(
-        dm.map
-            .iter()
-            .enumerate()
-            .filter(|(_, d)| **d > 2.0 && **d < 4.0)
-            .filter(|(i, _)| layer.tiles[*i].tile_type == TileType::Floor)
-            .nth(0)
-            .unwrap()
-            .0,
-    );
-    spawn_colony_secbot(ecs, secbot_tile, 3);
-
-    // Rushed
-    let mut n = 0;
-    while n < 30 {
+    {
         let mut rng_lock = crate::RNG.lock();
-        let rng = rng_lock.as_mut().unwrap();
-        let pt = Point::new(rng.range(0, WIDTH), rng.range(0, HEIGHT));
-        let idx = layer.point2d_to_index(pt);
-        let d = DistanceAlg::Pythagoras.distance2d(layer.starting_point, pt);
-        if layer.tiles[idx].tile_type == TileType::Floor && d > 12.0 {
-            n += 1;
-            match rng.range(0, 5) {
-                0 => spawn_dead_colonist(ecs, pt, 3),
-                1 => spawn_face_eater(ecs, pt, 3),
-                2 => spawn_xeno_egg(ecs, pt, 3, rng.roll_dice(1, 6)),
-                3 => spawn_quill_worm(ecs, pt, 3),
-                4 => spawn_xenomorph(ecs, pt, 3),
-                _ => {}
-            }
+        place_vaults(&mut layer, ecs, depth, rng_lock.as_mut().unwrap());
+    }
+
+    if queen {
+        // Spawn the queen far away, unless a vault already placed her
+        let has_queen = <(&Name, &Position)>::query()
+            .iter(ecs)
+            .any(|(name, pos)| name.0 == "Alien Queen" && pos.layer == depth);
+        if !has_queen {
+            let farthest_tile = layer.index_to_point2d(
+                dm.map
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, d)| **d < std::f32::MAX)
+                    .filter(|(i, _)| layer.tiles[*i].tile_type == TileType::Floor)
+                    .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap())
+                    .unwrap()
+                    .0,
+            );
+            spawn_monster(ecs, "Alien Queen", farthest_tile, depth);
         }
+
+        // Spawn the other SecBot near the entrance
+        let secbot_tile = layer.index_to_point2d(
+            dm.map
+                .iter()
+                .enumerate()
+                .filter(|(_, d)| **d > 2.0 && **d < 4.0)
+                .filter(|(i, _)| layer.tiles[*i].tile_type == TileType::Floor)
+                .nth(0)
+                .unwrap()
+                .0,
+        );
+        spawn_colony_secbot(ecs, secbot_tile, depth);
     }
 
+    let start = layer.starting_point;
+    spawn_from_table(&mut layer, ecs, depth, start);
+
     layer
 }
 
@@ -1,4 +1,5 @@
 use crate::components::*;
+use crate::raws::{ColonistKind, RAWS};
 use bracket_lib::prelude::*;
 use lazy_static::*;
 use legion::{systems::CommandBuffer, *};
@@ -8,12 +9,13 @@ fn build_base_colonist(
     ecs: &mut World,
     location: Point,
     layer: u32,
-    weapon: Option<i32>,
+    weapon: Option<&str>,
+    rng: &mut RandomNumberGenerator,
 ) -> Entity {
     let name_lock = NAMES.lock();
-    let name = name_lock.unwrap().random_human_name();
+    let name = name_lock.unwrap().random_human_name(rng);
     let entity = ecs.push((
-        Colonist { path: None, weapon },
+        Colonist { path: None },
         Position::with_pt(location, layer),
         Glyph {
             glyph: to_cp437('☺'),
@@ -26,7 +28,6 @@ fn build_base_colonist(
         CanBeActivated {},
     ));
 
-    let mut rng = RandomNumberGenerator::new();
     let hp = rng.roll_dice(1, 6) + 3;
     let mut commands = CommandBuffer::new(ecs);
     commands.add_component(
@@ -37,15 +38,24 @@ fn build_base_colonist(
         },
     );
     commands.add_component(entity, Blood(DARK_RED.into()));
+    commands.add_component(entity, Energy::new(100));
+    if let Some(loadout) = weapon.and_then(|name| RAWS.loadout(name)) {
+        commands.add_component(entity, loadout);
+    }
     //commands.add_component(entity, Active{});
     commands.flush(ecs);
 
     entity
 }
 
-pub fn spawn_random_colonist(ecs: &mut World, location: Point, layer: u32) {
+pub fn spawn_random_colonist(
+    ecs: &mut World,
+    location: Point,
+    layer: u32,
+    rng: &mut RandomNumberGenerator,
+) {
     // Using this pattern because Legion has a limit to how many components it takes in a push
-    let entity = build_base_colonist(ecs, location, layer, None);
+    let entity = build_base_colonist(ecs, location, layer, None, rng);
     let mut commands = CommandBuffer::new(ecs);
     commands.add_component(
         entity,
@@ -56,8 +66,13 @@ pub fn spawn_random_colonist(ecs: &mut World, location: Point, layer: u32) {
     commands.flush(ecs);
 }
 
-pub fn spawn_first_colonist(ecs: &mut World, location: Point, layer: u32) {
-    let entity = build_base_colonist(ecs, location, layer, None);
+pub fn spawn_first_colonist(
+    ecs: &mut World,
+    location: Point,
+    layer: u32,
+    rng: &mut RandomNumberGenerator,
+) {
+    let entity = build_base_colonist(ecs, location, layer, None, rng);
     let mut commands = CommandBuffer::new(ecs);
     commands.add_component(
         entity,
@@ -74,8 +89,13 @@ pub fn spawn_first_colonist(ecs: &mut World, location: Point, layer: u32) {
     commands.flush(ecs);
 }
 
-pub fn spawn_napping_colonist(ecs: &mut World, location: Point, layer: u32) {
-    let entity = build_base_colonist(ecs, location, layer, None);
+pub fn spawn_napping_colonist(
+    ecs: &mut World,
+    location: Point,
+    layer: u32,
+    rng: &mut RandomNumberGenerator,
+) {
+    let entity = build_base_colonist(ecs, location, layer, None, rng);
     let mut commands = CommandBuffer::new(ecs);
     commands.add_component(
         entity,
@@ -90,8 +110,13 @@ pub fn spawn_napping_colonist(ecs: &mut World, location: Point, layer: u32) {
     commands.flush(ecs);
 }
 
-pub fn spawn_hiding_colonist(ecs: &mut World, location: Point, layer: u32) {
-    let entity = build_base_colonist(ecs, location, layer, None);
+pub fn spawn_hiding_colonist(
+    ecs: &mut World,
+    location: Point,
+    layer: u32,
+    rng: &mut RandomNumberGenerator,
+) {
+    let entity = build_base_colonist(ecs, location, layer, None, rng);
     let mut commands = CommandBuffer::new(ecs);
     commands.add_component(
         entity,
@@ -102,8 +127,13 @@ pub fn spawn_hiding_colonist(ecs: &mut World, location: Point, layer: u32) {
     commands.flush(ecs);
 }
 
-pub fn spawn_suicidal_colonist(ecs: &mut World, location: Point, layer: u32) {
-    let entity = build_base_colonist(ecs, location, layer, None);
+pub fn spawn_suicidal_colonist(
+    ecs: &mut World,
+    location: Point,
+    layer: u32,
+    rng: &mut RandomNumberGenerator,
+) {
+    let entity = build_base_colonist(ecs, location, layer, None, rng);
     let mut commands = CommandBuffer::new(ecs);
     commands.add_component(
         entity,
@@ -121,7 +151,13 @@ pub fn spawn_suicidal_colonist(ecs: &mut World, location: Point, layer: u32) {
     );
     commands.flush(ecs);
 
-    super::props::spawn_live_grenade(ecs, location + Point::new(-1, -1), layer);
+    super::props::spawn_prop(
+        ecs,
+        "live_grenade",
+        location + Point::new(-1, -1),
+        layer,
+        rng,
+    );
 }
 
 pub fn spawn_marine_colonist(
@@ -130,7 +166,7 @@ pub fn spawn_marine_colonist(
     layer: u32,
     rng: &mut RandomNumberGenerator,
 ) {
-    let entity = build_base_colonist(ecs, location, layer, Some(5));
+    let entity = build_base_colonist(ecs, location, layer, Some("Colonial Carbine"), rng);
     let mut commands = CommandBuffer::new(ecs);
     commands.add_component(
         entity,
@@ -152,8 +188,13 @@ pub fn spawn_marine_colonist(
     commands.flush(ecs);
 }
 
-pub fn spawn_marine_leader(ecs: &mut World, location: Point, layer: u32) {
-    let entity = build_base_colonist(ecs, location, layer, Some(5));
+pub fn spawn_marine_leader(
+    ecs: &mut World,
+    location: Point,
+    layer: u32,
+    rng: &mut RandomNumberGenerator,
+) {
+    let entity = build_base_colonist(ecs, location, layer, Some("Colonial Carbine"), rng);
     let mut commands = CommandBuffer::new(ecs);
     commands.add_component(
         entity,
@@ -170,14 +211,16 @@ pub fn spawn_marine_leader(ecs: &mut World, location: Point, layer: u32) {
     commands.flush(ecs);
 }
 
-pub fn spawn_dead_colonist(ecs: &mut World, location: Point, layer: u32) {
+pub fn spawn_dead_colonist(
+    ecs: &mut World,
+    location: Point,
+    layer: u32,
+    rng: &mut RandomNumberGenerator,
+) {
     let name_lock = NAMES.lock();
-    let name = name_lock.unwrap().random_human_name();
+    let name = name_lock.unwrap().random_human_name(rng);
     ecs.push((
-        Colonist {
-            path: None,
-            weapon: None,
-        },
+        Colonist { path: None },
         Position::with_pt(location, layer),
         Glyph {
             glyph: to_cp437('☺'),
@@ -190,14 +233,16 @@ pub fn spawn_dead_colonist(ecs: &mut World, location: Point, layer: u32) {
     ));
 }
 
-pub fn spawn_dead_doctor(ecs: &mut World, location: Point, layer: u32) {
+pub fn spawn_dead_doctor(
+    ecs: &mut World,
+    location: Point,
+    layer: u32,
+    rng: &mut RandomNumberGenerator,
+) {
     let name_lock = NAMES.lock();
-    let name = name_lock.unwrap().random_human_name();
+    let name = name_lock.unwrap().random_human_name(rng);
     ecs.push((
-        Colonist {
-            path: None,
-            weapon: None,
-        },
+        Colonist { path: None },
         Position::with_pt(location, layer),
         Glyph {
             glyph: to_cp437('☺'),
@@ -212,10 +257,7 @@ pub fn spawn_dead_doctor(ecs: &mut World, location: Point, layer: u32) {
 
 pub fn spawn_dead_xeno(ecs: &mut World, location: Point, layer: u32) {
     ecs.push((
-        Colonist {
-            path: None,
-            weapon: None,
-        },
+        Colonist { path: None },
         Position::with_pt(location, layer),
         Glyph {
             glyph: to_cp437('x'),
@@ -257,9 +299,36 @@ pub fn spawn_colony_secbot(ecs: &mut World, location: Point, layer: u32) {
             max: 10,
         },
     );
+    commands.add_component(e, Energy::new(100));
+    if let Some(loadout) = RAWS.loadout("Defence Cannon") {
+        commands.add_component(e, loadout);
+    }
     commands.flush(ecs);
 }
 
+/// Spawns one of the colonist types named in the room raws.
+pub fn spawn_colonist(
+    ecs: &mut World,
+    kind: &ColonistKind,
+    location: Point,
+    layer: u32,
+    rng: &mut RandomNumberGenerator,
+) {
+    match kind {
+        ColonistKind::Random => spawn_random_colonist(ecs, location, layer, rng),
+        ColonistKind::First => spawn_first_colonist(ecs, location, layer, rng),
+        ColonistKind::Napping => spawn_napping_colonist(ecs, location, layer, rng),
+        ColonistKind::Hiding => spawn_hiding_colonist(ecs, location, layer, rng),
+        ColonistKind::Suicidal => spawn_suicidal_colonist(ecs, location, layer, rng),
+        ColonistKind::Marine => spawn_marine_colonist(ecs, location, layer, rng),
+        ColonistKind::MarineLeader => spawn_marine_leader(ecs, location, layer, rng),
+        ColonistKind::Dead => spawn_dead_colonist(ecs, location, layer, rng),
+        ColonistKind::DeadDoctor => spawn_dead_doctor(ecs, location, layer, rng),
+        ColonistKind::DeadXeno => spawn_dead_xeno(ecs, location, layer),
+        ColonistKind::SecBot => spawn_colony_secbot(ecs, location, layer),
+    }
+}
+
 /* Name Generation */
 
 const FIRST_NAMES_1: &str = include_str!("../../../resources/first_names_female.txt");
@@ -282,9 +351,8 @@ impl Names {
         }
     }
 
-    fn random_human_name(&self) -> String {
+    fn random_human_name(&self, rng: &mut RandomNumberGenerator) -> String {
         use inflector::Inflector;
-        let mut rng = RandomNumberGenerator::new(); // Avoiding locking issues
         let male = rng.range(0, 100) < 50;
         let first_source = match male {
             true => &self.male_first,
@@ -1,22 +1,36 @@
-use super::{all_space, colonists::*, edge_filler, props::*, spawn_face_eater, spawn_quill_worm};
+use super::{
+    add_door, all_space,
+    colonists::*,
+    edge_filler,
+    props::*,
+    rooms::{build_room, pick_template},
+    LayerBuilder,
+};
 use crate::{
     components::*,
     map::{tile::TileType, Layer, Tile, HEIGHT, WIDTH},
 };
 use bracket_lib::prelude::*;
 use legion::*;
+use std::collections::HashMap;
+
+pub struct Entrance {
+    pub rooms: usize,
+}
 
-pub fn build_entrance(ecs: &mut World) -> Layer {
-    let mut layer = Layer::new(std::usize::MAX, ecs); // Gets a default layer
+impl LayerBuilder for Entrance {
+    fn build(&self, ecs: &mut World, depth: u32) -> Layer {
+        let mut layer = Layer::new();
 
-    all_space(&mut layer);
-    add_landscape(&mut layer, ecs);
-    add_docking_capsule(&mut layer, ecs);
+        all_space(&mut layer);
+        add_landscape(&mut layer, ecs);
+        add_docking_capsule(&mut layer, ecs, depth, self.rooms);
 
-    layer
+        layer
+    }
 }
 
-fn add_docking_capsule(map: &mut Layer, ecs: &mut World) {
+fn add_docking_capsule(map: &mut Layer, ecs: &mut World, depth: u32, num_rooms: usize) {
     const MIDDLE: usize = HEIGHT / 2;
     const TOP: usize = MIDDLE - 3;
     const BOTTOM: usize = MIDDLE + 3;
@@ -56,44 +70,47 @@ fn add_docking_capsule(map: &mut Layer, ecs: &mut World) {
     let idx = map.point2d_to_index(Point::new(x_middle + 2, BOTTOM + 1));
     map.tiles[idx] = Tile::capsule_window();
     ecs.push((
-        Position::with_pt(Point::new(x_middle - 2, TOP - 1), 0),
+        Position::with_pt(Point::new(x_middle - 2, TOP - 1), depth),
         Description("A window. It doesn't look fun outside.".to_string()),
     ));
     ecs.push((
-        Position::with_pt(Point::new(x_middle - 2, BOTTOM + 1), 0),
+        Position::with_pt(Point::new(x_middle - 2, BOTTOM + 1), depth),
         Description("A window. It doesn't look fun outside.".to_string()),
     ));
     ecs.push((
-        Position::with_pt(Point::new(x_middle + 2, TOP - 1), 0),
+        Position::with_pt(Point::new(x_middle + 2, TOP - 1), depth),
         Description("A window. It doesn't look fun outside.".to_string()),
     ));
     ecs.push((
-        Position::with_pt(Point::new(x_middle + 2, BOTTOM + 1), 0),
+        Position::with_pt(Point::new(x_middle + 2, BOTTOM + 1), depth),
         Description("A window. It doesn't look fun outside.".to_string()),
     ));
 
-    // Spawn the game exit
-    add_game_exit(map, ecs, Point::new(LEFT - 1, MIDDLE));
+    // The way out is through the back of the capsule
+    let exit = Point::new(LEFT - 1, MIDDLE);
+    let exit_idx = map.point2d_to_index(exit);
+    map.tiles[exit_idx] = Tile::stairs_up();
+    map.colonist_exit = exit;
 
     // Start adding in building complex features
-    add_door(map, ecs, Point::new(RIGHT + 1, MIDDLE));
+    add_door(map, ecs, Point::new(RIGHT + 1, MIDDLE), depth);
     let start_room = add_entryway(map, ecs, Point::new(RIGHT + 1, MIDDLE));
     let mut rooms = vec![start_room];
-    while rooms.len() < 12 {
-        try_random_room(map, ecs, &mut rooms);
+    while rooms.len() < num_rooms {
+        try_random_room(map, ecs, &mut rooms, depth);
     }
 
     // Fill in the edges
     edge_filler(map);
 
     // Add some exterior windows
-    add_windows(map, ecs);
+    add_windows(map, ecs, depth);
 
     // Add an exit
-    add_exit(&mut rooms, map, ecs);
+    add_exit(&mut rooms, map);
 
     // Populate rooms
-    populate_rooms(&mut rooms, map, ecs);
+    populate_rooms(&mut rooms, map, ecs, depth);
 
     // Smooth the walls
     super::smooth_walls(map);
@@ -108,21 +125,6 @@ fn add_docking_capsule(map: &mut Layer, ecs: &mut World) {
     map.starting_point = Point::new(LEFT + 1, MIDDLE);
 }
 
-fn add_game_exit(map: &mut Layer, ecs: &mut World, pt: Point) {
-    let exit_idx = map.point2d_to_index(pt);
-    map.tiles[exit_idx] = Tile::game_over();
-    map.colonist_exit = pt;
-
-    ecs.push((
-        Position::with_pt(pt, 0),
-        Description(
-            "Exit to SecBot's Ship. Leave through here when you are ready to call it game over."
-                .to_string(),
-        ),
-        TileTrigger(crate::components::TriggerType::EndGame),
-    ));
-}
-
 fn add_landscape(map: &mut Layer, _ecs: &mut World) {
     let mut rng_lock = crate::RNG.lock();
     let rng = rng_lock.as_mut().unwrap();
@@ -143,23 +145,6 @@ fn add_landscape(map: &mut Layer, _ecs: &mut World) {
     }
 }
 
-fn add_door(map: &mut Layer, ecs: &mut World, pt: Point) {
-    let idx = map.point2d_to_index(pt);
-    ecs.push((
-        Position::with_pt(pt, 0),
-        Description("A heavy, steel door.".to_string()),
-        Glyph {
-            glyph: to_cp437('+'),
-            color: ColorPair::new(CYAN, BLACK),
-        },
-        Door {},
-    ));
-    map.tiles[idx] = Tile::wall();
-    map.tiles[idx].glyph = to_cp437('+');
-    map.tiles[idx].color.fg = CYAN.into();
-    map.is_door[idx] = true;
-}
-
 fn add_entryway(map: &mut Layer, _ecs: &mut World, entrance: Point) -> Rect {
     let room = Rect::with_size(entrance.x + 1, entrance.y - 5, 20, 10);
     fill_room(map, &room);
@@ -193,7 +178,7 @@ fn fill_room(map: &mut Layer, room: &Rect) {
     }
 }
 
-fn try_random_room(map: &mut Layer, ecs: &mut World, rooms: &mut Vec<Rect>) {
+fn try_random_room(map: &mut Layer, ecs: &mut World, rooms: &mut Vec<Rect>, depth: u32) {
     let mut rng_lock = crate::RNG.lock();
     let rng = rng_lock.as_mut().unwrap();
     if let Some(parent_room) = rng.random_slice_entry(&rooms) {
@@ -263,7 +248,7 @@ fn try_random_room(map: &mut Layer, ecs: &mut World, rooms: &mut Vec<Rect>) {
             });
 
             if can_add {
-                add_door(map, ecs, Point::new(x, y));
+                add_door(map, ecs, Point::new(x, y), depth);
                 fill_room(map, &new_room);
                 rooms.push(new_room);
             }
@@ -271,7 +256,7 @@ fn try_random_room(map: &mut Layer, ecs: &mut World, rooms: &mut Vec<Rect>) {
     }
 }
 
-fn add_windows(map: &mut Layer, ecs: &mut World) {
+fn add_windows(map: &mut Layer, ecs: &mut World, depth: u32) {
     let mut rng_lock = crate::RNG.lock();
     let rng = rng_lock.as_mut().unwrap();
 
@@ -288,7 +273,7 @@ fn add_windows(map: &mut Layer, ecs: &mut World) {
                     if rng.range(0, 10) == 0 {
                         map.tiles[idx] = Tile::window();
                         ecs.push((
-                            Position::with_pt(Point::new(x, y), 0),
+                            Position::with_pt(Point::new(x, y), depth),
                             Description(
                                 "A window. Not sure who thought that was a good idea.".to_string(),
                             ),
@@ -300,47 +285,30 @@ fn add_windows(map: &mut Layer, ecs: &mut World) {
     }
 }
 
-fn add_exit(rooms: &mut Vec<Rect>, map: &mut Layer, ecs: &mut World) {
+fn add_exit(rooms: &mut Vec<Rect>, map: &mut Layer) {
     let mut rng_lock = crate::RNG.lock();
     let rng = rng_lock.as_mut().unwrap();
     let room = rng.random_slice_entry(&rooms).unwrap();
     let exit_location = room.center();
     let idx = map.point2d_to_index(exit_location);
     map.tiles[idx] = Tile::stairs_down();
-
-    ecs.push((
-        Position::with_pt(exit_location, 0),
-        Description("Stairs further into the complex".to_string()),
-    ));
 }
 
-fn populate_rooms(rooms: &Vec<Rect>, map: &mut Layer, ecs: &mut World) {
+fn populate_rooms(rooms: &Vec<Rect>, map: &mut Layer, ecs: &mut World, depth: u32) {
     let mut rng_lock = crate::RNG.lock();
     let rng = rng_lock.as_mut().unwrap();
 
     // The first room always contains a single colonist, who must be alive.
-    entryway(&rooms[0], map, ecs, rng);
+    entryway(&rooms[0], map, ecs, depth, rng);
 
-    // Each room after that can be random. This is an initial, very boring spawn to get
-    // the colonist functionality going.
-    let mut room_types = Vec::new();
-    for i in 0..MAX_ROOM_TYPES {
-        room_types.push(i);
-    }
+    // Each room after that picks a template from the room raws
+    let mut used = HashMap::new();
     let stairs = map.find_down_stairs();
     rooms.iter().skip(1).for_each(|r| {
         if !r.point_set().contains(&stairs) {
-            if !room_types.is_empty() {
-                let room_index = rng.random_slice_index(&room_types).unwrap();
-                let ri = room_types[room_index];
-                room_types.remove(room_index);
-                spawn_room(ri, r, map, ecs, rng);
-            } else {
-                if rng.range(0, 5) == 0 {
-                    spawn_random_colonist(ecs, r.center(), 0);
-                } else {
-                    spawn_face_eater(ecs, r.center(), 0);
-                }
+            if let Some(template) = pick_template(r, &used, rng) {
+                *used.entry(template.name.clone()).or_insert(0) += 1;
+                build_room(template, r, map, ecs, depth, rng);
             }
         }
     });
@@ -355,211 +323,38 @@ fn get_random_point(points: &mut Vec<Point>, rng: &mut RandomNumberGenerator) ->
     result
 }
 
-fn entryway(room: &Rect, map: &mut Layer, ecs: &mut World, rng: &mut RandomNumberGenerator) {
-    let mut open_space = Vec::new();
-    room.for_each(|p| {
-        if p != map.starting_point {
-            open_space.push(p)
-        }
-    });
-
-    // Spawn the colonist who greets you
-    spawn_first_colonist(ecs, get_random_point(&mut open_space, rng), 0);
-    spawn_explosive_barrel(ecs, get_random_point(&mut open_space, rng), 0);
-    spawn_explosive_barrel(ecs, get_random_point(&mut open_space, rng), 0);
-    spawn_soda_machine(ecs, get_random_point(&mut open_space, rng), 0);
-    spawn_snack_machine(ecs, get_random_point(&mut open_space, rng), 0);
-    spawn_greeter(ecs, get_random_point(&mut open_space, rng), 0);
-    for _ in 0..10 {
-        let point = get_random_point(&mut open_space, rng);
-        if open_space.contains(&(point + Point::new(1, 0))) {
-            spawn_chair(ecs, point, 0);
-            spawn_table(ecs, point + Point::new(1, 0), 0);
-        }
-    }
-}
-
-const MAX_ROOM_TYPES: usize = 12;
-
-fn spawn_room(
-    rt: usize,
+fn entryway(
     room: &Rect,
     map: &mut Layer,
     ecs: &mut World,
+    depth: u32,
     rng: &mut RandomNumberGenerator,
 ) {
-    match rt {
-        0 => charnel_house(room, map, ecs),
-        1 => bedroom(room, map, ecs, rng),
-        2 => bedroom_not_so_nice(room, map, ecs, rng),
-        3 => charnel_house_with_fe(room, map, ecs),
-        4 => hidey_boom(room, ecs),
-        5 => med_bay(room, ecs, map),
-        7 => hydroponics(room, ecs, map, rng),
-        8 => hydroponics(room, ecs, map, rng),
-        9 => hydroponic_monstrous(room, ecs, map, rng),
-        10 => suicidal_colonist_room(room, ecs),
-        11 => hydroponic_ranged_monstrous(room, ecs, map, rng),
-        _ => {}
-    }
-}
-
-fn charnel_house(room: &Rect, map: &mut Layer, ecs: &mut World) {
-    room.for_each(|pt| {
-        let idx = map.point2d_to_index(pt);
-        map.tiles[idx].color.fg = DARK_RED.into();
-    });
-    spawn_dead_colonist(ecs, room.center() + Point::new(-1, 0), 0);
-    spawn_dead_colonist(ecs, room.center() + Point::new(1, 0), 0);
-}
-
-fn charnel_house_with_fe(room: &Rect, map: &mut Layer, ecs: &mut World) {
-    room.for_each(|pt| {
-        let idx = map.point2d_to_index(pt);
-        map.tiles[idx].color.fg = DARK_RED.into();
-    });
-    spawn_dead_colonist(ecs, room.center() + Point::new(-1, 0), 0);
-    spawn_dead_colonist(ecs, room.center() + Point::new(1, 0), 0);
-    spawn_face_eater(ecs, room.center(), 0);
-}
-
-fn bedroom(room: &Rect, map: &mut Layer, ecs: &mut World, rng: &mut RandomNumberGenerator) {
     let mut open_space = Vec::new();
     room.for_each(|p| {
         if p != map.starting_point {
             open_space.push(p)
         }
     });
-    let pt = get_random_point(&mut open_space, rng);
-    spawn_napping_colonist(ecs, pt, 0);
-    spawn_bed(ecs, pt, 0);
-}
 
-fn bedroom_not_so_nice(
-    room: &Rect,
-    map: &mut Layer,
-    ecs: &mut World,
-    rng: &mut RandomNumberGenerator,
-) {
-    let mut open_space = Vec::new();
-    room.for_each(|p| {
-        if p != map.starting_point {
-            open_space.push(p)
-        }
-    });
+    // Spawn the colonist who greets you
     let pt = get_random_point(&mut open_space, rng);
-    spawn_napping_colonist(ecs, pt, 0);
-    spawn_bed(ecs, pt, 0);
-    spawn_face_eater(ecs, Point::new(room.x1, room.y1), 0);
-    spawn_face_eater(ecs, Point::new(room.x2, room.y1), 0);
-    spawn_face_eater(ecs, Point::new(room.x1, room.y2), 0);
-}
-
-fn hidey_boom(room: &Rect, ecs: &mut World) {
-    room.for_each(|pt| {
-        if pt != room.center() {
-            spawn_explosive_barrel(ecs, pt, 0);
-        }
-    });
-    spawn_hiding_colonist(ecs, room.center(), 0);
-}
-
-fn med_bay(room: &Rect, ecs: &mut World, map: &mut Layer) {
-    let c = room.center();
-    let idx = map.point2d_to_index(c);
-    map.tiles[idx] = Tile::healing();
-    spawn_random_colonist(ecs, c + Point::new(1, 0), 0);
-    ecs.push((
-        Position::with_pt(c, 0),
-        Description("This auto-doc loves healing SecBots!".to_string()),
-        TileTrigger(crate::components::TriggerType::Healing),
-    ));
-}
-
-fn hydroponics(room: &Rect, ecs: &mut World, map: &mut Layer, rng: &mut RandomNumberGenerator) {
-    room.for_each(|pt| {
-        let idx = map.point2d_to_index(pt);
-        map.tiles[idx].color.fg = GREEN.into();
-    });
-    let mut open_space = Vec::new();
-    room.for_each(|p| {
-        if p != map.starting_point {
-            open_space.push(p)
-        }
-    });
-    for _ in 0..10 {
-        if !open_space.is_empty() {
-            let pt = get_random_point(&mut open_space, rng);
-            spawn_tree(ecs, pt, 0);
-        }
-    }
-}
-
-fn hydroponic_monstrous(
-    room: &Rect,
-    ecs: &mut World,
-    map: &mut Layer,
-    rng: &mut RandomNumberGenerator,
-) {
-    room.for_each(|pt| {
-        let idx = map.point2d_to_index(pt);
-        map.tiles[idx].color.fg = RED.into();
-    });
-    let mut open_space = Vec::new();
-    room.for_each(|p| {
-        if p != map.starting_point {
-            open_space.push(p)
-        }
-    });
-    for _ in 0..10 {
-        if !open_space.is_empty() {
-            let pt = get_random_point(&mut open_space, rng);
-            if rng.range(1, 10) < 5 {
-                spawn_tree(ecs, pt, 0)
-            } else {
-                spawn_face_eater(ecs, pt, 0);
-            }
-        }
-    }
-}
-
-fn hydroponic_ranged_monstrous(
-    room: &Rect,
-    ecs: &mut World,
-    map: &mut Layer,
-    rng: &mut RandomNumberGenerator,
-) {
-    room.for_each(|pt| {
-        let idx = map.point2d_to_index(pt);
-        map.tiles[idx].color.fg = RED.into();
-    });
-    let mut open_space = Vec::new();
-    room.for_each(|p| {
-        if p != map.starting_point {
-            open_space.push(p)
-        }
-    });
-    let mut n = 0;
+    spawn_first_colonist(ecs, pt, depth, rng);
+    let pt = get_random_point(&mut open_space, rng);
+    spawn_prop(ecs, "explosive_barrel", pt, depth, rng);
+    let pt = get_random_point(&mut open_space, rng);
+    spawn_prop(ecs, "explosive_barrel", pt, depth, rng);
+    let pt = get_random_point(&mut open_space, rng);
+    spawn_prop(ecs, "soda_machine", pt, depth, rng);
+    let pt = get_random_point(&mut open_space, rng);
+    spawn_prop(ecs, "snack_machine", pt, depth, rng);
+    let pt = get_random_point(&mut open_space, rng);
+    spawn_prop(ecs, "greeter", pt, depth, rng);
     for _ in 0..10 {
-        if !open_space.is_empty() {
-            let pt = get_random_point(&mut open_space, rng);
-            if rng.range(1, 10) < 5 {
-                spawn_tree(ecs, pt, 0)
-            } else {
-                if n < 2 {
-                    spawn_quill_worm(ecs, pt, 0);
-                    n += 1;
-                }
-            }
+        let point = get_random_point(&mut open_space, rng);
+        if open_space.contains(&(point + Point::new(1, 0))) {
+            spawn_prop(ecs, "chair", point, depth, rng);
+            spawn_prop(ecs, "table", point + Point::new(1, 0), depth, rng);
         }
     }
 }
-
-fn suicidal_colonist_room(room: &Rect, ecs: &mut World) {
-    let c = room.center();
-    spawn_suicidal_colonist(ecs, c, 0);
-    spawn_face_eater(ecs, Point::new(room.x1, room.y1), 0);
-    spawn_face_eater(ecs, Point::new(room.x2, room.y1), 0);
-    spawn_face_eater(ecs, Point::new(room.x1, room.y2), 0);
-    spawn_face_eater(ecs, Point::new(room.x2, room.y2), 0);
-}
@@ -0,0 +1,25 @@
+use crate::components::*;
+use crate::raws::{colour, RAWS};
+use bracket_lib::prelude::*;
+use legion::*;
+
+/// Spawns an item on the ground from its entry in `items.ron`.
+pub fn spawn_item(ecs: &mut World, id: &str, pos: Point, layer: u32) {
+    let def = RAWS
+        .item(id)
+        .unwrap_or_else(|| panic!("Unknown item: {}", id));
+    ecs.push((
+        Item {
+            id: def.id.clone(),
+            name: def.name.clone(),
+            effect: def.effect,
+        },
+        Glyph {
+            glyph: to_cp437(def.glyph),
+            color: ColorPair::new(colour(&def.colour), BLACK),
+        },
+        Name(def.name.clone()),
+        Description(def.description.clone()),
+        Position::with_pt(pos, layer),
+    ));
+}
@@ -1,10 +1,20 @@
-use super::{all_wall, colonists::*, edge_filler, monsters::*, props::*};
+use super::{
+    all_wall, edge_filler, spawn_table::spawn_from_table, vaults::place_vaults, LayerBuilder,
+};
 use crate::map::{tile::TileType, Layer, Tile, HEIGHT, TILES, WIDTH};
 use bracket_lib::prelude::*;
 use legion::*;
 
-pub fn build_mine_middle(ecs: &mut World) -> Layer {
-    let mut layer = Layer::new(std::usize::MAX, ecs); // Gets a default layer
+pub struct MineMiddle;
+
+impl LayerBuilder for MineMiddle {
+    fn build(&self, ecs: &mut World, depth: u32) -> Layer {
+        build_mine_middle(ecs, depth)
+    }
+}
+
+fn build_mine_middle(ecs: &mut World, depth: u32) -> Layer {
+    let mut layer = Layer::new();
     all_wall(&mut layer);
     let center_pt: Point = Point::new(WIDTH / 2, HEIGHT / 2);
 
@@ -62,31 +72,13 @@ pub fn build_mine_middle(ecs: &mut World) -> Layer {
     });
 
     edge_filler(&mut layer);
-    super::smooth_walls(&mut layer);
-
-    // Go with a simple approach for now
-    let mut n = 0;
-    while n < 30 {
+    {
         let mut rng_lock = crate::RNG.lock();
-        let mut rng = rng_lock.as_mut().unwrap();
-        let pt = Point::new(rng.range(0, WIDTH), rng.range(0, HEIGHT));
-        let idx = layer.point2d_to_index(pt);
-        let d = DistanceAlg::Pythagoras.distance2d(center_pt, pt);
-        if layer.tiles[idx].tile_type == TileType::Floor && d > 12.0 {
-            n += 1;
-            match rng.range(0, 8) {
-                0 => spawn_random_colonist(ecs, pt, 2),
-                1 => spawn_marine_colonist(ecs, pt, 2, &mut rng),
-                2 => spawn_explosive_barrel(ecs, pt, 2),
-                3 => spawn_dead_colonist(ecs, pt, 2),
-                4 => spawn_face_eater(ecs, pt, 2),
-                5 => spawn_xeno_egg(ecs, pt, 2, rng.roll_dice(1, 6)),
-                6 => spawn_quill_worm(ecs, pt, 2),
-                7 => spawn_xenomorph(ecs, pt, 2),
-                _ => {}
-            }
-        }
+        place_vaults(&mut layer, ecs, depth, rng_lock.as_mut().unwrap());
     }
+    super::smooth_walls(&mut layer);
+
+    spawn_from_table(&mut layer, ecs, depth, center_pt);
 
     layer
 }
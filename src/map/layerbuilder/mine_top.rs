@@ -1,4 +1,6 @@
-use super::{all_wall, colonists::*, edge_filler, monsters::*, props::*};
+use super::{
+    all_wall, colonists::*, edge_filler, monsters::*, props::*, vaults::place_vaults, LayerBuilder,
+};
 use crate::{
     components::*,
     map::{tile::TileType, Layer, Tile, HEIGHT, WIDTH},
@@ -6,8 +8,18 @@ use crate::{
 use bracket_lib::prelude::*;
 use legion::*;
 
-pub fn build_mine_top(ecs: &mut World) -> Layer {
-    let mut layer = Layer::new(std::usize::MAX, ecs); // Gets a default layer
+pub struct MineTop {
+    pub rooms: usize,
+}
+
+impl LayerBuilder for MineTop {
+    fn build(&self, ecs: &mut World, depth: u32) -> Layer {
+        build_mine_top(ecs, depth, self.rooms)
+    }
+}
+
+fn build_mine_top(ecs: &mut World, depth: u32, num_rooms: usize) -> Layer {
+    let mut layer = Layer::new();
     all_wall(&mut layer);
     let center_pt: Point = Point::new(WIDTH / 2, HEIGHT / 2);
 
@@ -41,7 +53,7 @@ pub fn build_mine_top(ecs: &mut World) -> Layer {
     // Start building rooms and corridors
     // Using the Hands-On Rust rooms/corridors builder slightly modified to go towards the middle
     let mut rooms = vec![Rect::with_size((WIDTH / 2) - 10, (HEIGHT / 2) - 10, 20, 20)];
-    while rooms.len() < 14 {
+    while rooms.len() < num_rooms {
         try_room(&mut rooms, &layer);
     }
 
@@ -64,30 +76,31 @@ pub fn build_mine_top(ecs: &mut World) -> Layer {
     });
 
     edge_filler(&mut layer);
+    place_vaults(&mut layer, ecs, depth, rng);
 
     super::smooth_walls(&mut layer);
 
     // Start by building the melee that greets your arrival
     for x in center_pt.x - 6..=center_pt.x + 6 {
         if rng.range(0, 4) == 0 {
-            spawn_quill_worm(ecs, Point::new(x, center_pt.y - 10), 1);
-            spawn_quill_worm(ecs, Point::new(x, center_pt.y + 10), 1);
+            spawn_monster(ecs, "Quill Worm", Point::new(x, center_pt.y - 10), depth);
+            spawn_monster(ecs, "Quill Worm", Point::new(x, center_pt.y + 10), depth);
         }
         if rng.range(0, 3) == 0 {
-            spawn_face_eater(ecs, Point::new(x, center_pt.y - 9), 1);
-            spawn_face_eater(ecs, Point::new(x, center_pt.y + 9), 1);
+            spawn_monster(ecs, "Face Eater", Point::new(x, center_pt.y - 9), depth);
+            spawn_monster(ecs, "Face Eater", Point::new(x, center_pt.y + 9), depth);
         }
     }
 
     // Spawn the defense squads
     for x in center_pt.x - 1..=center_pt.x + 1 {
-        spawn_marine_colonist(ecs, Point::new(x, center_pt.y - 5), 1, rng);
-        spawn_marine_colonist(ecs, Point::new(x, center_pt.y + 5), 1, rng);
+        spawn_marine_colonist(ecs, Point::new(x, center_pt.y - 5), depth, rng);
+        spawn_marine_colonist(ecs, Point::new(x, center_pt.y + 5), depth, rng);
     }
-    spawn_marine_leader(ecs, Point::new(center_pt.x, center_pt.y - 2), 1);
+    spawn_marine_leader(ecs, Point::new(center_pt.x, center_pt.y - 2), depth, rng);
 
     // Room-based population
-    populate_rooms(&rooms, &mut layer, ecs, rng);
+    populate_rooms(&rooms, &mut layer, ecs, depth, rng);
 
     layer
 }
@@ -138,6 +151,7 @@ fn populate_rooms(
     rooms: &Vec<Rect>,
     map: &mut Layer,
     ecs: &mut World,
+    depth: u32,
     rng: &mut RandomNumberGenerator,
 ) {
     // Each room after that can be random. This is an initial, very boring spawn to get
@@ -153,12 +167,12 @@ fn populate_rooms(
                 let room_index = rng.random_slice_index(&room_types).unwrap();
                 let ri = room_types[room_index];
                 room_types.remove(room_index);
-                spawn_room(ri, r, map, ecs, rng);
+                spawn_room(ri, r, map, ecs, depth, rng);
             } else {
                 if rng.range(0, 5) == 0 {
-                    spawn_random_colonist(ecs, r.center(), 1);
+                    spawn_random_colonist(ecs, r.center(), depth, rng);
                 } else {
-                    spawn_face_eater(ecs, r.center(), 1);
+                    spawn_monster(ecs, "Face Eater", r.center(), depth);
                 }
             }
         }
@@ -179,54 +193,73 @@ fn spawn_room(
     room: &Rect,
     map: &mut Layer,
     ecs: &mut World,
+    depth: u32,
     rng: &mut RandomNumberGenerator,
 ) {
     match rt {
-        0 => charnel_house(room, map, ecs, rng),
-        1 => med_bay(room, ecs, map, rng),
-        2 => improvised_boomer(room, ecs, rng),
-        3 => hydroponics(room, ecs, map, rng),
-        4 => hydroponic_eggs(room, ecs, map, rng),
-        5 => enter_the_xeno(room, ecs),
-        6 => break_room(room, ecs, rng),
-        7 => doctor_evil(room, ecs, map),
-        8 => volatile_storage(room, ecs, rng),
+        0 => charnel_house(room, map, ecs, depth, rng),
+        1 => med_bay(room, ecs, map, depth, rng),
+        2 => improvised_boomer(room, ecs, depth, rng),
+        3 => hydroponics(room, ecs, map, depth, rng),
+        4 => hydroponic_eggs(room, ecs, map, depth, rng),
+        5 => enter_the_xeno(room, ecs, depth, rng),
+        6 => break_room(room, ecs, depth, rng),
+        7 => doctor_evil(room, ecs, map, depth, rng),
+        8 => volatile_storage(room, ecs, depth, rng),
         _ => {}
     }
 }
 
-fn charnel_house(room: &Rect, map: &mut Layer, ecs: &mut World, rng: &mut RandomNumberGenerator) {
+fn charnel_house(
+    room: &Rect,
+    map: &mut Layer,
+    ecs: &mut World,
+    depth: u32,
+    rng: &mut RandomNumberGenerator,
+) {
     room.for_each(|pt| {
         let idx = map.point2d_to_index(pt);
         map.tiles[idx].color.fg = DARK_RED.into();
         if rng.range(0, 10) == 0 {
-            spawn_dead_colonist(ecs, pt, 1);
+            spawn_dead_colonist(ecs, pt, depth, rng);
         }
     });
 }
 
-fn med_bay(room: &Rect, ecs: &mut World, map: &mut Layer, rng: &mut RandomNumberGenerator) {
+fn med_bay(
+    room: &Rect,
+    ecs: &mut World,
+    map: &mut Layer,
+    depth: u32,
+    rng: &mut RandomNumberGenerator,
+) {
     let c = room.center();
     let idx = map.point2d_to_index(c);
     map.tiles[idx] = Tile::healing();
-    spawn_marine_colonist(ecs, c + Point::new(1, 0), 1, rng);
+    spawn_marine_colonist(ecs, c + Point::new(1, 0), depth, rng);
     ecs.push((
-        Position::with_pt(c, 1),
+        Position::with_pt(c, depth),
         Description("This auto-doc loves healing SecBots!".to_string()),
         TileTrigger(crate::components::TriggerType::Healing),
     ));
 }
 
-fn improvised_boomer(room: &Rect, ecs: &mut World, rng: &mut RandomNumberGenerator) {
+fn improvised_boomer(room: &Rect, ecs: &mut World, depth: u32, rng: &mut RandomNumberGenerator) {
     room.for_each(|pt| match rng.range(0, 5) {
-        0 => spawn_explosive_barrel(ecs, pt, 1),
-        1 => spawn_face_eater(ecs, pt, 1),
-        2 => spawn_live_grenade(ecs, pt, 1),
+        0 => spawn_prop(ecs, "explosive_barrel", pt, depth, rng),
+        1 => spawn_monster(ecs, "Face Eater", pt, depth),
+        2 => spawn_prop(ecs, "live_grenade", pt, depth, rng),
         _ => {}
     });
 }
 
-fn hydroponics(room: &Rect, ecs: &mut World, map: &mut Layer, rng: &mut RandomNumberGenerator) {
+fn hydroponics(
+    room: &Rect,
+    ecs: &mut World,
+    map: &mut Layer,
+    depth: u32,
+    rng: &mut RandomNumberGenerator,
+) {
     room.for_each(|pt| {
         let idx = map.point2d_to_index(pt);
         map.tiles[idx].color.fg = GREEN.into();
@@ -240,12 +273,18 @@ fn hydroponics(room: &Rect, ecs: &mut World, map: &mut Layer, rng: &mut RandomNu
     for _ in 0..10 {
         if !open_space.is_empty() {
             let pt = get_random_point(&mut open_space, rng);
-            spawn_tree(ecs, pt, 1);
+            spawn_prop(ecs, "tree", pt, depth, rng);
         }
     }
 }
 
-fn hydroponic_eggs(room: &Rect, ecs: &mut World, map: &mut Layer, rng: &mut RandomNumberGenerator) {
+fn hydroponic_eggs(
+    room: &Rect,
+    ecs: &mut World,
+    map: &mut Layer,
+    depth: u32,
+    rng: &mut RandomNumberGenerator,
+) {
     room.for_each(|pt| {
         let idx = map.point2d_to_index(pt);
         map.tiles[idx].color.fg = GREEN.into();
@@ -260,54 +299,63 @@ fn hydroponic_eggs(room: &Rect, ecs: &mut World, map: &mut Layer, rng: &mut Rand
         if !open_space.is_empty() {
             let pt = get_random_point(&mut open_space, rng);
             if rng.range(0, 2) == 0 {
-                spawn_tree(ecs, pt, 1);
+                spawn_prop(ecs, "tree", pt, depth, rng);
             } else {
-                spawn_xeno_egg(ecs, pt, 1, rng.range(3, 10));
+                spawn_prop(ecs, "dormant_xeno_egg", pt, depth, rng);
             }
         }
     }
 }
 
-fn enter_the_xeno(room: &Rect, ecs: &mut World) {
-    spawn_random_colonist(ecs, room.center(), 1);
-    spawn_xenomorph(ecs, room.center() + Point::new(1, 0), 1);
-    spawn_dead_colonist(ecs, room.center() + Point::new(-1, 0), 1);
+fn enter_the_xeno(room: &Rect, ecs: &mut World, depth: u32, rng: &mut RandomNumberGenerator) {
+    spawn_random_colonist(ecs, room.center(), depth, rng);
+    spawn_monster(ecs, "Xenomorph", room.center() + Point::new(1, 0), depth);
+    spawn_dead_colonist(ecs, room.center() + Point::new(-1, 0), depth, rng);
 }
 
-fn break_room(room: &Rect, ecs: &mut World, rng: &mut RandomNumberGenerator) {
+fn break_room(room: &Rect, ecs: &mut World, depth: u32, rng: &mut RandomNumberGenerator) {
     let mut open_space = Vec::new();
     room.for_each(|p| open_space.push(p));
 
     // Spawn the colonist who greets you
-    spawn_soda_machine(ecs, get_random_point(&mut open_space, rng), 1);
-    spawn_snack_machine(ecs, get_random_point(&mut open_space, rng), 1);
-    spawn_greeter(ecs, get_random_point(&mut open_space, rng), 1);
+    let pt = get_random_point(&mut open_space, rng);
+    spawn_prop(ecs, "soda_machine", pt, depth, rng);
+    let pt = get_random_point(&mut open_space, rng);
+    spawn_prop(ecs, "snack_machine", pt, depth, rng);
+    let pt = get_random_point(&mut open_space, rng);
+    spawn_prop(ecs, "greeter", pt, depth, rng);
     for _ in 0..10 {
         let point = get_random_point(&mut open_space, rng);
         if open_space.contains(&(point + Point::new(1, 1))) {
-            spawn_chair(ecs, point, 1);
-            spawn_table(ecs, point + Point::new(1, 0), 1);
+            spawn_prop(ecs, "chair", point, depth, rng);
+            spawn_prop(ecs, "table", point + Point::new(1, 0), depth, rng);
         }
     }
 }
 
-fn doctor_evil(room: &Rect, ecs: &mut World, map: &mut Layer) {
+fn doctor_evil(
+    room: &Rect,
+    ecs: &mut World,
+    map: &mut Layer,
+    depth: u32,
+    rng: &mut RandomNumberGenerator,
+) {
     let c = room.center();
     let idx = map.point2d_to_index(c);
     map.tiles[idx] = Tile::healing();
-    spawn_dead_doctor(ecs, c + Point::new(-1, 0), 1);
-    spawn_dead_xeno(ecs, c + Point::new(-1, -1), 1);
+    spawn_dead_doctor(ecs, c + Point::new(-1, 0), depth, rng);
+    spawn_dead_xeno(ecs, c + Point::new(-1, -1), depth);
     ecs.push((
-        Position::with_pt(c, 1),
+        Position::with_pt(c, depth),
         Description("This auto-doc loves healing SecBots!".to_string()),
         TileTrigger(crate::components::TriggerType::Healing),
     ));
 }
 
-fn volatile_storage(room: &Rect, ecs: &mut World, rng: &mut RandomNumberGenerator) {
+fn volatile_storage(room: &Rect, ecs: &mut World, depth: u32, rng: &mut RandomNumberGenerator) {
     room.for_each(|pt| {
         if rng.range(0, 3) == 0 {
-            spawn_explosive_barrel(ecs, pt, 1);
+            spawn_prop(ecs, "explosive_barrel", pt, depth, rng);
         }
     });
 }
@@ -4,14 +4,114 @@ mod mine_middle;
 mod mine_top;
 use super::{tile::TileType, Layer, Tile, HEIGHT, WIDTH};
 mod colonists;
-pub use caverns::build_caverns;
-pub use entrance::build_entrance;
-pub use mine_middle::build_mine_middle;
-pub use mine_top::build_mine_top;
+use caverns::Caverns;
+use entrance::Entrance;
+use mine_middle::MineMiddle;
+use mine_top::MineTop;
 pub mod monsters;
-use bracket_lib::prelude::{to_cp437, Algorithm2D, Point};
+use crate::{components::*, raws::LayerDef};
+use bracket_lib::prelude::{
+    to_cp437, Algorithm2D, ColorPair, DijkstraMap, Point, BLACK, CYAN, GOLD,
+};
+use legion::{Entity, World};
 pub use monsters::*;
+pub mod items;
+pub use items::*;
 mod props;
+pub use props::{spawn_active_prop, spawn_prop};
+mod rooms;
+mod spawn_table;
+mod vaults;
+
+pub trait LayerBuilder {
+    /// Builds the layer at `depth`. Builders always place a way up (at
+    /// `colonist_exit`) and a way down; `build_layer` fixes those up for the
+    /// top and bottom of the stack.
+    fn build(&self, ecs: &mut World, depth: u32) -> Layer;
+}
+
+fn builder_for(def: &LayerDef) -> Box<dyn LayerBuilder> {
+    match def {
+        LayerDef::Entrance { rooms } => Box::new(Entrance { rooms: *rooms }),
+        LayerDef::MineTop { rooms } => Box::new(MineTop { rooms: *rooms }),
+        LayerDef::MineMiddle => Box::new(MineMiddle),
+        LayerDef::Caverns { queen } => Box::new(Caverns { queen: *queen }),
+    }
+}
+
+pub fn build_layer(def: &LayerDef, ecs: &mut World, depth: u32, is_last: bool) -> Layer {
+    let mut layer = builder_for(def).build(ecs, depth);
+    fix_stairs(&mut layer, ecs, depth, is_last);
+    layer
+}
+
+fn fix_stairs(layer: &mut Layer, ecs: &mut World, depth: u32, is_last: bool) {
+    // The way up from the top layer leads back to the ship
+    let exit_idx = layer.point2d_to_index(layer.colonist_exit);
+    if depth == 0 {
+        layer.tiles[exit_idx] = Tile::game_over();
+        ecs.push((
+            Position::with_pt(layer.colonist_exit, 0),
+            Description(
+                "Exit to SecBot's Ship. Leave through here when you are ready to call it game over."
+                    .to_string(),
+            ),
+            TileTrigger(TriggerType::EndGame),
+        ));
+        // Don't start the game standing on the exit
+        if layer.starting_point == layer.colonist_exit {
+            let exit = layer.colonist_exit;
+            if let Some(pt) = [(1, 0), (-1, 0), (0, 1), (0, -1)]
+                .iter()
+                .map(|(x, y)| exit + Point::new(*x, *y))
+                .find(|pt| {
+                    layer.in_bounds(*pt) && !layer.tiles[layer.point2d_to_index(*pt)].blocked
+                })
+            {
+                layer.starting_point = pt;
+            }
+        }
+    } else {
+        layer.tiles[exit_idx] = Tile::stairs_up();
+    }
+
+    let stairs_down: Vec<usize> = layer
+        .tiles
+        .iter()
+        .enumerate()
+        .filter(|(_, t)| t.tile_type == TileType::StairsDown)
+        .map(|(idx, _)| idx)
+        .collect();
+    if is_last {
+        stairs_down
+            .iter()
+            .for_each(|idx| layer.tiles[*idx] = Tile::floor());
+        return;
+    }
+
+    // Builders meant for the bottom don't dig their own way down, so use the
+    // farthest reachable floor.
+    let down_idx = if let Some(idx) = stairs_down.first() {
+        *idx
+    } else {
+        let start = layer.point2d_to_index(layer.starting_point);
+        let dm = DijkstraMap::new(WIDTH, HEIGHT, &[start], layer, (WIDTH * HEIGHT) as f32);
+        let idx = dm
+            .map
+            .iter()
+            .enumerate()
+            .filter(|(i, d)| layer.tiles[*i].tile_type == TileType::Floor && **d < std::f32::MAX)
+            .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap())
+            .map(|(i, _)| i)
+            .unwrap_or(start);
+        layer.tiles[idx] = Tile::stairs_down();
+        idx
+    };
+    ecs.push((
+        Position::with_pt(layer.index_to_point2d(down_idx), depth),
+        Description("Stairs further into the complex".to_string()),
+    ));
+}
 
 fn all_space(layer: &mut Layer) {
     layer.tiles.iter_mut().for_each(|t| {
@@ -63,15 +163,51 @@ fn edge_filler_lava(map: &mut Layer) {
     }
 }
 
+fn add_door(map: &mut Layer, ecs: &mut World, pt: Point, depth: u32) -> Entity {
+    let idx = map.point2d_to_index(pt);
+    let door = ecs.push((
+        Position::with_pt(pt, depth),
+        Description("A heavy, steel door.".to_string()),
+        Glyph {
+            glyph: to_cp437('+'),
+            color: ColorPair::new(CYAN, BLACK),
+        },
+        Door {},
+        Health {
+            max: 15,
+            current: 15,
+        },
+    ));
+    map.tiles[idx] = Tile::wall();
+    map.tiles[idx].glyph = to_cp437('+');
+    map.tiles[idx].color.fg = CYAN.into();
+    map.is_door[idx] = true;
+    door
+}
+
+fn lock_door(ecs: &mut World, door: Entity) {
+    if let Some(mut entry) = ecs.entry(door) {
+        entry.add_component(Locked {});
+        entry.add_component(Description(
+            "A heavy, steel door with a keycard lock.".to_string(),
+        ));
+        if let Ok(glyph) = entry.get_component_mut::<Glyph>() {
+            glyph.color.fg = GOLD.into();
+        }
+    }
+}
+
 fn is_wall_for_smoothing(idx: usize, map: &Layer) -> bool {
-    map.tiles[idx].tile_type == TileType::Wall || map.tiles[idx].glyph == to_cp437('+')
+    map.tiles[idx].tile_type == TileType::Wall
+        || map.tiles[idx].tile_type == TileType::Window
+        || map.tiles[idx].glyph == to_cp437('+')
 }
 
 fn smooth_walls(map: &mut Layer) {
     for y in 1..HEIGHT - 1 {
         for x in 1..WIDTH - 1 {
             let idx = map.point2d_to_index(Point::new(x, y));
-            if map.tiles[idx].tile_type == TileType::Wall && map.tiles[idx].glyph != to_cp437('+') {
+            if is_wall_for_smoothing(idx, map) && map.tiles[idx].glyph != to_cp437('+') {
                 let mut mask: u8 = 0;
                 if is_wall_for_smoothing(idx - WIDTH, map) {
                     mask += 1;
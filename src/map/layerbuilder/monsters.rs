@@ -1,126 +1,47 @@
 use crate::components::*;
+use crate::raws::{colour, RAWS};
 use bracket_lib::prelude::*;
 use legion::*;
 use std::collections::HashSet;
 
-pub fn spawn_face_eater(ecs: &mut World, location: Point, layer: u32) {
+/// Spawns a hostile from its entry in `monsters.ron`.
+pub fn spawn_monster(ecs: &mut World, name: &str, location: Point, layer: u32) {
+    let def = RAWS
+        .monster(name)
+        .unwrap_or_else(|| panic!("Unknown monster: {}", name));
     let entity = ecs.push((
-        Name("Face Eater".to_string()),
+        Name(def.name.clone()),
         Hostile {
-            ranged: Vec::new(),
-            melee: vec![Melee { damage: 1 }],
+            ranged: def.ranged.clone(),
+            melee: def.melee.clone(),
+            path: None,
         },
         Targetable {},
         Position::with_pt(location, layer),
         Glyph {
-            glyph: 157, // Yen symbol
-            color: ColorPair::new(RED, BLACK),
+            glyph: to_cp437(def.glyph),
+            color: ColorPair::new(colour(&def.colour), BLACK),
         },
-        Description("Nasty eight-legged beastie that likes to eat faces.".to_string()),
-        Health { max: 3, current: 3 },
-        Blood(DARK_GREEN.into()),
-    ));
-    let mut commands = legion::systems::CommandBuffer::new(ecs);
-    commands.add_component(
-        entity,
-        FieldOfView {
-            radius: 6,
-            visible_tiles: HashSet::new(),
-        },
-    );
-    commands.add_component(entity, CanBeActivated {});
-    commands.flush(ecs);
-}
-
-pub fn spawn_quill_worm(ecs: &mut World, location: Point, layer: u32) {
-    let entity = ecs.push((
-        Name("Quill Worm".to_string()),
-        Hostile {
-            ranged: vec![Ranged { power: 1 }],
-            melee: Vec::new(),
-        },
-        Targetable {},
-        Position::with_pt(location, layer),
-        Glyph {
-            glyph: to_cp437('q'),
-            color: ColorPair::new(RED, BLACK),
-        },
-        Description("Kinda like a porcupine if H.R. Giger had designed it".to_string()),
-        Health { max: 3, current: 3 },
-        Blood(DARK_GREEN.into()),
-    ));
-    let mut commands = legion::systems::CommandBuffer::new(ecs);
-    commands.add_component(
-        entity,
-        FieldOfView {
-            radius: 6,
-            visible_tiles: HashSet::new(),
-        },
-    );
-    commands.add_component(entity, CanBeActivated {});
-    commands.flush(ecs);
-}
-
-pub fn spawn_xenomorph(ecs: &mut World, location: Point, layer: u32) {
-    let entity = ecs.push((
-        Name("Xenomorph".to_string()),
-        Hostile {
-            ranged: vec![Ranged { power: 2 }],
-            melee: Vec::new(),
-        },
-        Targetable {},
-        Position::with_pt(location, layer),
-        Glyph {
-            glyph: to_cp437('X'),
-            color: ColorPair::new(RED, BLACK),
-        },
-        Description("A dog-like creature, if dogs had human faces and spat acid.".to_string()),
-        Health {
-            max: 10,
-            current: 10,
-        },
-        Blood(DARK_GREEN.into()),
-    ));
-    let mut commands = legion::systems::CommandBuffer::new(ecs);
-    commands.add_component(
-        entity,
-        FieldOfView {
-            radius: 6,
-            visible_tiles: HashSet::new(),
-        },
-    );
-    commands.add_component(entity, CanBeActivated {});
-    commands.flush(ecs);
-}
-
-pub fn spawn_queen(ecs: &mut World, location: Point, layer: u32) {
-    let entity = ecs.push((
-        Name("Alien Queen".to_string()),
-        Hostile {
-            ranged: vec![Ranged { power: 5 }],
-            melee: Vec::new(),
-        },
-        Targetable {},
-        Position::with_pt(location, layer),
-        Glyph {
-            glyph: to_cp437('Q'),
-            color: ColorPair::new(RED, BLACK),
-        },
-        Description("A strangely beautiful giant alien".to_string()),
+        Description(def.description.clone()),
         Health {
-            max: 50,
-            current: 50,
+            max: def.health,
+            current: def.health,
         },
-        Blood(DARK_GREEN.into()),
+        Blood(colour(&def.blood)),
     ));
     let mut commands = legion::systems::CommandBuffer::new(ecs);
     commands.add_component(
         entity,
         FieldOfView {
-            radius: 8,
+            radius: def.fov_radius,
             visible_tiles: HashSet::new(),
         },
     );
     commands.add_component(entity, CanBeActivated {});
+    commands.add_component(entity, Energy::new(def.speed));
+    commands.add_component(entity, def.behaviour.to_behaviour(location));
+    if !def.resistances.is_empty() {
+        commands.add_component(entity, Resistances(def.resistances.clone()));
+    }
     commands.flush(ecs);
 }
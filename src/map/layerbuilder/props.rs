@@ -1,199 +1,97 @@
 use crate::components::*;
+use crate::raws::{colour, RAWS};
 use bracket_lib::prelude::*;
 use legion::systems::CommandBuffer;
 use legion::*;
 
-pub fn spawn_soda_machine(ecs: &mut World, pos: Point, layer: u32) {
-    ecs.push((
-        Glyph {
-            glyph: to_cp437('◘'),
-            color: ColorPair::new(YELLOW, BLACK),
-        },
-        Name("Soda Machine".to_string()),
-        Description("A powered-down soda machine".to_string()),
-        Health { current: 3, max: 3 },
-        Targetable {},
-        Position::with_pt(pos, layer),
-        PropertyValue(100),
-        SetDecoration {},
-    ));
-}
-
-pub fn spawn_snack_machine(ecs: &mut World, pos: Point, layer: u32) {
-    ecs.push((
-        Glyph {
-            glyph: to_cp437('◘'),
-            color: ColorPair::new(MAGENTA, BLACK),
-        },
-        Name("Snack Machine".to_string()),
-        Description("A powered-down snack machine".to_string()),
-        Health { current: 3, max: 3 },
-        Targetable {},
-        Position::with_pt(pos, layer),
-        PropertyValue(100),
-        SetDecoration {},
-    ));
-}
-
-pub fn spawn_chair(ecs: &mut World, pos: Point, layer: u32) {
-    ecs.push((
-        Glyph {
-            glyph: to_cp437('╓'),
-            color: ColorPair::new(WHITE, BLACK),
-        },
-        Name("Plastic Chair".to_string()),
-        Description("A plastic chair".to_string()),
-        Health { current: 1, max: 1 },
-        Targetable {},
-        Position::with_pt(pos, layer),
-        PropertyValue(5),
-        SetDecoration {},
-    ));
+/// Spawns a prop from its entry in `props.ron`.
+pub fn spawn_prop(
+    ecs: &mut World,
+    id: &str,
+    pos: Point,
+    layer: u32,
+    rng: &mut RandomNumberGenerator,
+) {
+    build_prop(ecs, id, pos, layer, rng);
 }
 
-pub fn spawn_table(ecs: &mut World, pos: Point, layer: u32) {
-    ecs.push((
-        Glyph {
-            glyph: to_cp437('╥'),
-            color: ColorPair::new(WHITE, BLACK),
-        },
-        Name("Plastic Table".to_string()),
-        Description("A plastic table".to_string()),
-        Health { current: 2, max: 2 },
-        Targetable {},
-        Position::with_pt(pos, layer),
-        PropertyValue(10),
-        SetDecoration {},
-    ));
+/// Spawns a prop that's already awake, so its timer runs whether or not the
+/// player can see it.
+pub fn spawn_active_prop(
+    ecs: &mut World,
+    id: &str,
+    pos: Point,
+    layer: u32,
+    rng: &mut RandomNumberGenerator,
+) {
+    let e = build_prop(ecs, id, pos, layer, rng);
+    if let Some(mut entry) = ecs.entry(e) {
+        entry.remove_component::<CanBeActivated>();
+        entry.add_component(Active {});
+    }
 }
 
-pub fn spawn_greeter(ecs: &mut World, pos: Point, layer: u32) {
+fn build_prop(
+    ecs: &mut World,
+    id: &str,
+    pos: Point,
+    layer: u32,
+    rng: &mut RandomNumberGenerator,
+) -> Entity {
+    let def = RAWS
+        .prop(id)
+        .unwrap_or_else(|| panic!("Unknown prop: {}", id));
     let e = ecs.push((
         Glyph {
-            glyph: to_cp437('♥'),
-            color: ColorPair::new(WHITE, BLACK),
-        },
-        Name("GreeterBot".to_string()),
-        Description("Bracket Corp welcoming robot. Your safety is important to us!".to_string()),
-        Health { current: 2, max: 2 },
-        Targetable {},
-        Position::with_pt(pos, layer),
-        PropertyValue(100),
-        SetDecoration {},
-    ));
-    let mut commands = CommandBuffer::new(ecs);
-    commands.add_component(
-        e,
-        Dialog {
-            lines: vec![
-                "Welcome to Bracket 394!".to_string(),
-                "Your safety is important to us.".to_string(),
-                "Please wear a hard hat at all times.".to_string(),
-                "We hope you enjoy your mining experience!".to_string(),
-            ],
+            glyph: to_cp437(def.glyph),
+            color: ColorPair::new(colour(&def.colour), BLACK),
         },
-    );
-    commands.add_component(e, CanBeActivated {});
-    commands.flush(ecs);
-}
-
-pub fn spawn_bed(ecs: &mut World, pos: Point, layer: u32) {
-    ecs.push((
-        Glyph {
-            glyph: to_cp437('ß'),
-            color: ColorPair::new(WHITE, BLACK),
+        Name(def.name.clone()),
+        Description(def.description.clone()),
+        Health {
+            current: def.health,
+            max: def.health,
         },
-        Name("Comfy Bed".to_string()),
-        Description("A really comfortable bed".to_string()),
-        Health { current: 5, max: 5 },
         Targetable {},
         Position::with_pt(pos, layer),
-        PropertyValue(100),
-        SetDecoration {},
-    ));
-}
-
-pub fn spawn_tree(ecs: &mut World, pos: Point, layer: u32) {
-    ecs.push((
-        Glyph {
-            glyph: to_cp437('♣'),
-            color: ColorPair::new(WHITE, BLACK),
-        },
-        Name("Bonsai Tree".to_string()),
-        Description("A small tree, providing oxygenation.".to_string()),
-        Health { current: 5, max: 5 },
-        Targetable {},
-        Position::with_pt(pos, layer),
-        PropertyValue(500),
-        SetDecoration {},
-    ));
-}
-
-pub fn spawn_explosive_barrel(ecs: &mut World, pos: Point, layer: u32) {
-    let e = ecs.push((
-        Glyph {
-            glyph: to_cp437('O'),
-            color: ColorPair::new(WHITE, BLACK),
-        },
-        Name("Explosive Barrel".to_string()),
-        Description("Why do people ALWAYS leave these lying around?".to_string()),
-        Health { current: 5, max: 5 },
-        Targetable {},
-        Position::with_pt(pos, layer),
-        PropertyValue(50),
         SetDecoration {},
     ));
     let mut commands = CommandBuffer::new(ecs);
-    commands.add_component(e, Explosive { range: 3 });
-    commands.flush(ecs);
-}
-
-pub fn spawn_live_grenade(ecs: &mut World, pos: Point, layer: u32) {
-    let e = ecs.push((
-        Glyph {
-            glyph: to_cp437('g'),
-            color: ColorPair::new(ORANGE, BLACK),
-        },
-        Name("Live Grenade".to_string()),
-        Description("Ticking time-bomg, counting down to an explosion.".to_string()),
-        Health { current: 1, max: 1 },
-        Targetable {},
-        Position::with_pt(pos, layer),
-        SetDecoration {},
-        CanBeActivated {},
-    ));
-    let mut commands = CommandBuffer::new(ecs);
-    commands.add_component(
-        e,
-        TimedEvent {
-            timer: 3,
-            event: EventType::Boom,
-        },
-    );
-    commands.flush(ecs);
-}
-
-pub fn spawn_xeno_egg(ecs: &mut World, pos: Point, layer: u32, timer: i32) {
-    let e = ecs.push((
-        Glyph {
-            glyph: to_cp437('○'),
-            color: ColorPair::new(LIME_GREEN, BLACK),
-        },
-        Name("Pulsing Egg".to_string()),
-        Description("A green egg. It pulses menacingly.".to_string()),
-        Health { current: 1, max: 1 },
-        Targetable {},
-        Position::with_pt(pos, layer),
-        SetDecoration {},
-        CanBeActivated {},
-    ));
-    let mut commands = CommandBuffer::new(ecs);
-    commands.add_component(
-        e,
-        TimedEvent {
-            timer,
-            event: EventType::HatchXenomorph,
-        },
-    );
+    if let Some(value) = def.property_value {
+        commands.add_component(e, PropertyValue(value));
+    }
+    if let Some(range) = def.explosive {
+        commands.add_component(e, Explosive { range });
+    }
+    if let Some(timer) = &def.timer {
+        let turns = if timer.max > timer.min {
+            rng.range(timer.min, timer.max + 1)
+        } else {
+            timer.min
+        };
+        commands.add_component(
+            e,
+            TimedEvent {
+                timer: turns,
+                event: timer.event.clone(),
+            },
+        );
+    }
+    if !def.resistances.is_empty() {
+        commands.add_component(e, Resistances(def.resistances.clone()));
+    }
+    if let Some(lines) = &def.dialog {
+        commands.add_component(
+            e,
+            Dialog {
+                lines: lines.clone(),
+            },
+        );
+    }
+    // Anything that talks or ticks needs to be woken up by the player
+    if def.timer.is_some() || def.dialog.is_some() {
+        commands.add_component(e, CanBeActivated {});
+    }
     commands.flush(ecs);
+    e
 }
@@ -0,0 +1,195 @@
+use super::{colonists::spawn_colonist, props::spawn_prop, spawn_item, spawn_monster};
+use crate::{
+    components::*,
+    map::{Layer, Tile, TileType},
+    raws::{colour, Location, RoomTemplate, RoomTile, SpawnDef, RAWS},
+};
+use bracket_lib::prelude::*;
+use legion::*;
+use std::collections::HashMap;
+
+fn room_tile(tile: RoomTile) -> Tile {
+    match tile {
+        RoomTile::Floor => Tile::floor(),
+        RoomTile::Wall => Tile::wall(),
+        RoomTile::Window => Tile::window(),
+    }
+}
+
+/// Picks a room template that fits `room`, honoring weights and per-map limits.
+pub fn pick_template(
+    room: &Rect,
+    used: &HashMap<String, i32>,
+    rng: &mut RandomNumberGenerator,
+) -> Option<&'static RoomTemplate> {
+    let fits: Vec<&RoomTemplate> = RAWS
+        .rooms
+        .iter()
+        .filter(|t| {
+            let (w, h) = (room.width(), room.height());
+            w >= t.min_size && h >= t.min_size && w <= t.max_size && h <= t.max_size
+        })
+        .filter(|t| match t.max_per_map {
+            Some(max) => used.get(&t.name).copied().unwrap_or(0) < max,
+            None => true,
+        })
+        .collect();
+
+    let weighted: Vec<&RoomTemplate> = fits.iter().filter(|t| t.weight > 0).copied().collect();
+    let total: i32 = weighted.iter().map(|t| t.weight).sum();
+    if total > 0 {
+        let mut roll = rng.range(0, total);
+        for t in weighted {
+            if roll < t.weight {
+                return Some(t);
+            }
+            roll -= t.weight;
+        }
+    }
+
+    let fallback: Vec<&RoomTemplate> = fits.iter().filter(|t| t.weight == 0).copied().collect();
+    rng.random_slice_entry(&fallback).copied()
+}
+
+pub fn build_room(
+    template: &RoomTemplate,
+    room: &Rect,
+    map: &mut Layer,
+    ecs: &mut World,
+    layer: u32,
+    rng: &mut RandomNumberGenerator,
+) {
+    // Swap tiles before tinting them. Only plain floor and wall are replaced,
+    // so stairs and doors stay put.
+    if let Some(tile) = template.floor {
+        room.for_each(|pt| {
+            let idx = map.point2d_to_index(pt);
+            if map.tiles[idx].tile_type == TileType::Floor {
+                map.tiles[idx] = room_tile(tile);
+            }
+        });
+    }
+    if let Some(tile) = template.wall {
+        for y in room.y1 - 1..=room.y2 + 1 {
+            for x in room.x1 - 1..=room.x2 + 1 {
+                let pt = Point::new(x, y);
+                if map.in_bounds(pt) && !room.point_in_rect(pt) {
+                    let idx = map.point2d_to_index(pt);
+                    if map.tiles[idx].tile_type == TileType::Wall && !map.is_door[idx] {
+                        map.tiles[idx] = room_tile(tile);
+                    }
+                }
+            }
+        }
+    }
+    if let Some(fg) = &template.floor_colour {
+        let fg = colour(fg);
+        room.for_each(|pt| {
+            let idx = map.point2d_to_index(pt);
+            map.tiles[idx].color.fg = fg.into();
+        });
+    }
+    if let Some(fg) = &template.wall_colour {
+        let fg = colour(fg);
+        for y in room.y1 - 1..=room.y2 + 1 {
+            for x in room.x1 - 1..=room.x2 + 1 {
+                let pt = Point::new(x, y);
+                if map.in_bounds(pt) {
+                    let idx = map.point2d_to_index(pt);
+                    if map.tiles[idx].blocked && !map.is_door[idx] {
+                        map.tiles[idx].color.fg = fg.into();
+                    }
+                }
+            }
+        }
+    }
+
+    let mut open_space = Vec::new();
+    room.for_each(|p| {
+        if p != map.starting_point {
+            open_space.push(p)
+        }
+    });
+
+    for placement in template.placements.iter() {
+        let points = match placement.at {
+            Location::Centre => claim(&mut open_space, vec![room.center()]),
+            Location::Offset(x, y) => {
+                claim(&mut open_space, vec![room.center() + Point::new(x, y)])
+            }
+            Location::Corners(n) => claim(
+                &mut open_space,
+                vec![
+                    Point::new(room.x1, room.y1),
+                    Point::new(room.x2, room.y1),
+                    Point::new(room.x1, room.y2),
+                    Point::new(room.x2, room.y2),
+                ]
+                .into_iter()
+                .take(n)
+                .collect(),
+            ),
+            Location::RandomOpen(n) => {
+                let mut points = Vec::new();
+                for _ in 0..n {
+                    if let Some(index) = rng.random_slice_index(&open_space) {
+                        points.push(open_space.remove(index));
+                    }
+                }
+                points
+            }
+            Location::AllOpen => open_space.drain(..).collect(),
+        };
+        for pt in points {
+            for spawn in placement.spawn.iter() {
+                spawn_entry(spawn, pt, map, ecs, layer, rng);
+            }
+        }
+    }
+}
+
+// Fixed locations are used whether or not something else is there, but
+// random placements won't pick them afterwards.
+fn claim(open_space: &mut Vec<Point>, points: Vec<Point>) -> Vec<Point> {
+    open_space.retain(|p| !points.contains(p));
+    points
+}
+
+pub fn spawn_entry(
+    spawn: &SpawnDef,
+    pt: Point,
+    map: &mut Layer,
+    ecs: &mut World,
+    layer: u32,
+    rng: &mut RandomNumberGenerator,
+) {
+    match spawn {
+        SpawnDef::Prop(id) => spawn_prop(ecs, id, pt, layer, rng),
+        SpawnDef::Monster(name) => spawn_monster(ecs, name, pt, layer),
+        SpawnDef::Item(id) => spawn_item(ecs, id, pt, layer),
+        SpawnDef::Colonist(kind) => spawn_colonist(ecs, kind, pt, layer, rng),
+        SpawnDef::OneOf(options) => {
+            let total: i32 = options.iter().map(|(weight, _)| *weight).sum();
+            if total > 0 {
+                let mut roll = rng.range(0, total);
+                for (weight, option) in options.iter() {
+                    if roll < *weight {
+                        spawn_entry(option, pt, map, ecs, layer, rng);
+                        return;
+                    }
+                    roll -= *weight;
+                }
+            }
+        }
+        SpawnDef::Nothing => {}
+        SpawnDef::AutoDoc => {
+            let idx = map.point2d_to_index(pt);
+            map.tiles[idx] = Tile::healing();
+            ecs.push((
+                Position::with_pt(pt, layer),
+                Description("This auto-doc loves healing SecBots!".to_string()),
+                TileTrigger(crate::components::TriggerType::Healing),
+            ));
+        }
+    }
+}
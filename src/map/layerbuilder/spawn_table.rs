@@ -0,0 +1,73 @@
+use super::rooms::spawn_entry;
+use crate::map::{tile::TileType, Layer};
+use crate::raws::{SpawnEntry, RAWS};
+use bracket_lib::prelude::*;
+use legion::*;
+
+/// Populates a layer from its entry in `spawns.ron`, keeping clear of `origin`.
+pub fn spawn_from_table(map: &mut Layer, ecs: &mut World, layer: u32, origin: Point) {
+    let table = if let Some(table) = RAWS.spawn_table(layer) {
+        table
+    } else {
+        return;
+    };
+    let mut rng_lock = crate::RNG.lock();
+    let rng = rng_lock.as_mut().unwrap();
+
+    let mut open_space: Vec<Point> = map
+        .tiles
+        .iter()
+        .enumerate()
+        .filter(|(_, t)| t.tile_type == TileType::Floor)
+        .map(|(idx, _)| map.index_to_point2d(idx))
+        .filter(|pt| DistanceAlg::Pythagoras.distance2d(origin, *pt) > table.min_distance)
+        .collect();
+
+    // Guaranteed spawns first, then fill up by weight
+    let mut counts = vec![0; table.entries.len()];
+    let mut picks = Vec::new();
+    for (i, entry) in table.entries.iter().enumerate() {
+        for _ in 0..entry.min {
+            picks.push(i);
+            counts[i] += 1;
+        }
+    }
+    while (picks.len() as i32) < table.total {
+        if let Some(i) = pick_entry(&table.entries, &counts, rng) {
+            picks.push(i);
+            counts[i] += 1;
+        } else {
+            break;
+        }
+    }
+
+    for i in picks {
+        if let Some(index) = rng.random_slice_index(&open_space) {
+            let pt = open_space.remove(index);
+            spawn_entry(&table.entries[i].spawn, pt, map, ecs, layer, rng);
+        }
+    }
+}
+
+fn pick_entry(
+    entries: &[SpawnEntry],
+    counts: &[i32],
+    rng: &mut RandomNumberGenerator,
+) -> Option<usize> {
+    let available: Vec<usize> = (0..entries.len())
+        .filter(|i| entries[*i].weight > 0)
+        .filter(|i| entries[*i].max.map_or(true, |max| counts[*i] < max))
+        .collect();
+    let total: i32 = available.iter().map(|i| entries[*i].weight).sum();
+    if total == 0 {
+        return None;
+    }
+    let mut roll = rng.range(0, total);
+    for i in available {
+        if roll < entries[i].weight {
+            return Some(i);
+        }
+        roll -= entries[i].weight;
+    }
+    None
+}
@@ -0,0 +1,132 @@
+use super::{add_door, lock_door, rooms::spawn_entry};
+use crate::{
+    map::{tile::TileType, Layer, Tile, HEIGHT, WIDTH},
+    raws::{vault_file, LegendEntry, VaultDef, VaultTile, RAWS},
+};
+use bracket_lib::prelude::*;
+use legion::*;
+
+const PLACEMENT_TRIES: usize = 50;
+
+/// Stamps any vaults meant for this depth into solid rock, next to open floor.
+pub fn place_vaults(map: &mut Layer, ecs: &mut World, depth: u32, rng: &mut RandomNumberGenerator) {
+    for vault in RAWS.vaults.vaults.iter() {
+        if vault.depths.contains(&depth) && rng.range(0, 100) < vault.chance {
+            let xp = load_vault(vault);
+            let drawing = &xp.layers[0];
+            if drawing.width + 2 >= WIDTH || drawing.height + 2 >= HEIGHT {
+                continue;
+            }
+            for _ in 0..PLACEMENT_TRIES {
+                let origin = Point::new(
+                    rng.range(1, WIDTH - drawing.width - 1),
+                    rng.range(1, HEIGHT - drawing.height - 1),
+                );
+                if fits(map, drawing, origin) {
+                    stamp(map, ecs, vault, drawing, origin, depth, rng);
+                    break;
+                }
+            }
+        }
+    }
+}
+
+fn load_vault(vault: &VaultDef) -> XpFile {
+    let mut bytes =
+        vault_file(&vault.file).unwrap_or_else(|| panic!("Missing vault file: {}", vault.file));
+    XpFile::read(&mut bytes).unwrap_or_else(|_| panic!("Unable to read vault: {}", vault.file))
+}
+
+fn legend_entry(cell: &XpCell) -> Option<&'static LegendEntry> {
+    if cell.bg.is_transparent() {
+        return None;
+    }
+    RAWS.vaults
+        .legend
+        .iter()
+        .find(|l| to_cp437(l.glyph) as u32 == cell.ch)
+}
+
+// A vault has to be drawn over solid rock, and at least one of its ways in
+// has to open onto floor.
+fn fits(map: &Layer, drawing: &XpLayer, origin: Point) -> bool {
+    let mut connected = false;
+    for y in 0..drawing.height {
+        for x in 0..drawing.width {
+            let pt = origin + Point::new(x, y);
+            let idx = map.point2d_to_index(pt);
+            if map.tiles[idx].tile_type != TileType::Wall
+                || map.is_door[idx]
+                || pt == map.starting_point
+                || pt == map.colonist_exit
+            {
+                return false;
+            }
+
+            let on_edge = x == 0 || y == 0 || x == drawing.width - 1 || y == drawing.height - 1;
+            let walkable = matches!(
+                drawing.get(x, y).and_then(legend_entry).map(|l| l.tile),
+                Some(VaultTile::Floor) | Some(VaultTile::Door)
+            );
+            if on_edge && walkable {
+                let outside = if x == 0 {
+                    pt + Point::new(-1, 0)
+                } else if x == drawing.width - 1 {
+                    pt + Point::new(1, 0)
+                } else if y == 0 {
+                    pt + Point::new(0, -1)
+                } else {
+                    pt + Point::new(0, 1)
+                };
+                if map.in_bounds(outside)
+                    && map.tiles[map.point2d_to_index(outside)].tile_type == TileType::Floor
+                {
+                    connected = true;
+                }
+            }
+        }
+    }
+    connected
+}
+
+fn stamp(
+    map: &mut Layer,
+    ecs: &mut World,
+    vault: &VaultDef,
+    drawing: &XpLayer,
+    origin: Point,
+    depth: u32,
+    rng: &mut RandomNumberGenerator,
+) {
+    for y in 0..drawing.height {
+        for x in 0..drawing.width {
+            let (cell, entry) = match drawing.get(x, y).map(|c| (c, legend_entry(c))) {
+                Some((cell, Some(entry))) => (cell, entry),
+                _ => continue,
+            };
+
+            let pt = origin + Point::new(x, y);
+            let idx = map.point2d_to_index(pt);
+            let tint = RGB::from_u8(cell.fg.r, cell.fg.g, cell.fg.b);
+            match entry.tile {
+                VaultTile::Wall => {
+                    map.tiles[idx] = Tile::wall();
+                    map.tiles[idx].color.fg = tint.into();
+                }
+                VaultTile::Floor => {
+                    map.tiles[idx] = Tile::floor();
+                    map.tiles[idx].color.fg = tint.into();
+                }
+                VaultTile::Door => {
+                    let door = add_door(map, ecs, pt, depth);
+                    if vault.locked {
+                        lock_door(ecs, door);
+                    }
+                }
+            }
+            if let Some(spawn) = &entry.spawn {
+                spawn_entry(spawn, pt, map, ecs, depth, rng);
+            }
+        }
+    }
+}
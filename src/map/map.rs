@@ -1,7 +1,10 @@
-use super::{Layer, NUM_LAYERS};
+use super::{layerbuilder::build_layer, Layer};
+use crate::raws::RAWS;
 use bracket_lib::prelude::*;
 use legion::World;
+use serde::{Deserialize, Serialize};
 
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Map {
     pub current_layer: usize,
     layers: Vec<Layer>,
@@ -9,10 +12,13 @@ pub struct Map {
 
 impl Map {
     pub fn new(ecs: &mut World) -> Self {
-        let mut layers = Vec::with_capacity(NUM_LAYERS);
-        for i in 0..NUM_LAYERS {
-            layers.push(Layer::new(i, ecs));
-        }
+        let last = RAWS.layers.len() - 1;
+        let layers = RAWS
+            .layers
+            .iter()
+            .enumerate()
+            .map(|(depth, def)| build_layer(def, ecs, depth as u32, depth == last))
+            .collect();
         Self {
             current_layer: 0, // TODO: Set me back
             layers,
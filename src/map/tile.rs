@@ -1,17 +1,19 @@
 use bracket_lib::prelude::*;
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
 pub enum TileType {
     Empty,
     Capsule,
     Wall,
+    Window,
     Floor,
     Outside,
     StairsDown,
     StairsUp,
 }
 
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Tile {
     pub glyph: FontCharType,
     pub color: ColorPair,
@@ -77,6 +79,16 @@ impl Tile {
             color: ColorPair::new(DARK_CYAN, BLACK),
             blocked: true,
             opaque: false,
+            tile_type: TileType::Window,
+        }
+    }
+
+    pub fn broken_window() -> Self {
+        Self {
+            glyph: to_cp437('%'),
+            color: ColorPair::new(GREY, BLACK),
+            blocked: true,
+            opaque: false,
             tile_type: TileType::Wall,
         }
     }
@@ -0,0 +1,12 @@
+use crate::components::ItemEffect;
+use serde::Deserialize;
+
+#[derive(Clone, Deserialize)]
+pub struct ItemDef {
+    pub id: String,
+    pub name: String,
+    pub glyph: char,
+    pub colour: String,
+    pub description: String,
+    pub effect: ItemEffect,
+}
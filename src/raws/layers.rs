@@ -0,0 +1,10 @@
+use serde::Deserialize;
+
+/// One entry in the layer stack, top first, with its builder's parameters.
+#[derive(Clone, Deserialize)]
+pub enum LayerDef {
+    Entrance { rooms: usize },
+    MineTop { rooms: usize },
+    MineMiddle,
+    Caverns { queen: bool },
+}
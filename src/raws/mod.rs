@@ -0,0 +1,93 @@
+mod items;
+mod layers;
+mod monsters;
+mod props;
+mod rooms;
+mod spawn_tables;
+mod vaults;
+mod weapons;
+use crate::components::Loadout;
+use bracket_lib::prelude::RGB;
+pub use items::*;
+pub use layers::*;
+use lazy_static::*;
+pub use monsters::*;
+pub use props::*;
+pub use rooms::*;
+pub use spawn_tables::*;
+pub use vaults::*;
+pub use weapons::*;
+
+// Raw files are embedded so that the WASM build doesn't need a file system.
+const ITEMS: &str = include_str!("../../resources/items.ron");
+const LAYERS: &str = include_str!("../../resources/layers.ron");
+const MONSTERS: &str = include_str!("../../resources/monsters.ron");
+const PROPS: &str = include_str!("../../resources/props.ron");
+const ROOMS: &str = include_str!("../../resources/rooms.ron");
+const SPAWNS: &str = include_str!("../../resources/spawns.ron");
+const VAULTS: &str = include_str!("../../resources/vaults.ron");
+const WEAPONS: &str = include_str!("../../resources/weapons.ron");
+
+lazy_static! {
+    pub static ref RAWS: Raws = Raws::load();
+}
+
+/// Game content that designers can edit without touching the code.
+pub struct Raws {
+    pub items: Vec<ItemDef>,
+    pub layers: Vec<LayerDef>,
+    pub monsters: Vec<MonsterDef>,
+    pub props: Vec<PropDef>,
+    pub rooms: Vec<RoomTemplate>,
+    pub spawn_tables: Vec<SpawnTable>,
+    pub vaults: Vaults,
+    pub weapons: Armoury,
+}
+
+impl Raws {
+    fn load() -> Self {
+        Self {
+            items: ron::de::from_str(ITEMS).expect("Unable to parse items.ron"),
+            layers: ron::de::from_str(LAYERS).expect("Unable to parse layers.ron"),
+            monsters: ron::de::from_str(MONSTERS).expect("Unable to parse monsters.ron"),
+            props: ron::de::from_str(PROPS).expect("Unable to parse props.ron"),
+            rooms: ron::de::from_str(ROOMS).expect("Unable to parse rooms.ron"),
+            spawn_tables: ron::de::from_str(SPAWNS).expect("Unable to parse spawns.ron"),
+            vaults: ron::de::from_str(VAULTS).expect("Unable to parse vaults.ron"),
+            weapons: ron::de::from_str(WEAPONS).expect("Unable to parse weapons.ron"),
+        }
+    }
+
+    pub fn item(&self, id: &str) -> Option<&ItemDef> {
+        self.items.iter().find(|i| i.id == id)
+    }
+
+    pub fn monster(&self, name: &str) -> Option<&MonsterDef> {
+        self.monsters.iter().find(|m| m.name == name)
+    }
+
+    pub fn prop(&self, id: &str) -> Option<&PropDef> {
+        self.props.iter().find(|p| p.id == id)
+    }
+
+    pub fn weapon(&self, name: &str) -> Option<&WeaponDef> {
+        self.weapons.weapons.iter().find(|w| w.name == name)
+    }
+
+    /// A single-weapon loadout, for colonists and bots.
+    pub fn loadout(&self, name: &str) -> Option<Loadout> {
+        self.weapon(name).map(|w| Loadout {
+            weapons: vec![w.to_weapon()],
+            current: 0,
+        })
+    }
+
+    pub fn spawn_table(&self, layer: u32) -> Option<&SpawnTable> {
+        self.spawn_tables.iter().find(|t| t.layer == layer)
+    }
+}
+
+/// Reads a "#RRGGBB" colour from a raw file.
+pub fn colour(hex: &str) -> RGB {
+    RGB::from_hex(hex).unwrap_or_else(|_| panic!("Bad colour in raws: {}", hex))
+}
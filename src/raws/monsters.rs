@@ -0,0 +1,49 @@
+use crate::components::{AiState, Behaviour, DamageType, Melee, Prey, Ranged};
+use bracket_lib::prelude::Point;
+use serde::Deserialize;
+
+#[derive(Clone, Deserialize)]
+pub struct MonsterDef {
+    pub name: String,
+    pub glyph: char,
+    pub colour: String,
+    pub description: String,
+    pub health: i32,
+    pub melee: Vec<Melee>,
+    pub ranged: Vec<Ranged>,
+    pub fov_radius: i32,
+    /// Energy gained per turn; 100 is one action a turn
+    pub speed: i32,
+    pub blood: String,
+    /// Percentage of each damage type taken; see `Resistances`
+    #[serde(default)]
+    pub resistances: Vec<(DamageType, i32)>,
+    #[serde(default)]
+    pub behaviour: BehaviourDef,
+}
+
+#[derive(Clone, Default, Deserialize)]
+pub struct BehaviourDef {
+    #[serde(default)]
+    pub default: AiState,
+    #[serde(default)]
+    pub flee_below: i32,
+    #[serde(default)]
+    pub keep_distance: i32,
+    #[serde(default)]
+    pub prefers: Prey,
+}
+
+impl BehaviourDef {
+    pub fn to_behaviour(&self, home: Point) -> Behaviour {
+        Behaviour {
+            state: self.default,
+            default: self.default,
+            flee_below: self.flee_below,
+            keep_distance: self.keep_distance,
+            prefers: self.prefers,
+            home,
+            last_seen: None,
+        }
+    }
+}
@@ -0,0 +1,31 @@
+use crate::components::{DamageType, EventType};
+use serde::Deserialize;
+
+#[derive(Clone, Deserialize)]
+pub struct PropDef {
+    pub id: String,
+    pub name: String,
+    pub glyph: char,
+    pub colour: String,
+    pub description: String,
+    pub health: i32,
+    #[serde(default)]
+    pub property_value: Option<i32>,
+    /// Blast range if the prop explodes when destroyed
+    #[serde(default)]
+    pub explosive: Option<i32>,
+    #[serde(default)]
+    pub timer: Option<TimerDef>,
+    #[serde(default)]
+    pub dialog: Option<Vec<String>>,
+    /// Percentage of each damage type taken; see `Resistances`
+    #[serde(default)]
+    pub resistances: Vec<(DamageType, i32)>,
+}
+
+#[derive(Clone, Deserialize)]
+pub struct TimerDef {
+    pub min: i32,
+    pub max: i32,
+    pub event: EventType,
+}
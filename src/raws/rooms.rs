@@ -0,0 +1,91 @@
+use serde::Deserialize;
+
+#[derive(Clone, Deserialize)]
+pub struct RoomTemplate {
+    pub name: String,
+    /// Relative chance of being picked. Zero-weight rooms are only used once
+    /// every weighted room has been used up.
+    pub weight: i32,
+    #[serde(default)]
+    pub max_per_map: Option<i32>,
+    /// Limits on the room's width and height
+    #[serde(default = "default_min_size")]
+    pub min_size: i32,
+    #[serde(default = "default_max_size")]
+    pub max_size: i32,
+    /// Replaces the room's floor and surrounding walls
+    #[serde(default)]
+    pub floor: Option<RoomTile>,
+    #[serde(default)]
+    pub wall: Option<RoomTile>,
+    #[serde(default)]
+    pub floor_colour: Option<String>,
+    #[serde(default)]
+    pub wall_colour: Option<String>,
+    #[serde(default)]
+    pub placements: Vec<Placement>,
+}
+
+fn default_min_size() -> i32 {
+    1
+}
+
+fn default_max_size() -> i32 {
+    100
+}
+
+#[derive(Clone, Copy, Deserialize)]
+pub enum RoomTile {
+    Floor,
+    Wall,
+    Window,
+}
+
+#[derive(Clone, Deserialize)]
+pub struct Placement {
+    pub at: Location,
+    pub spawn: Vec<SpawnDef>,
+}
+
+/// Where in a room a placement goes. Every point is only used once, in the
+/// order placements are listed.
+#[derive(Clone, Deserialize)]
+pub enum Location {
+    Centre,
+    /// Offset from the centre
+    Offset(i32, i32),
+    /// Up to four corners: top-left, top-right, bottom-left, bottom-right
+    Corners(usize),
+    /// Randomly chosen unused points
+    RandomOpen(usize),
+    /// Every point not used by an earlier placement
+    AllOpen,
+}
+
+#[derive(Clone, Deserialize)]
+pub enum SpawnDef {
+    Prop(String),
+    Monster(String),
+    Item(String),
+    Colonist(ColonistKind),
+    /// Weighted choice between spawns
+    OneOf(Vec<(i32, SpawnDef)>),
+    Nothing,
+    /// A healing tile
+    AutoDoc,
+}
+
+#[derive(Clone, Deserialize)]
+pub enum ColonistKind {
+    Random,
+    First,
+    Napping,
+    Hiding,
+    Suicidal,
+    Marine,
+    MarineLeader,
+    Dead,
+    DeadDoctor,
+    DeadXeno,
+    SecBot,
+}
@@ -0,0 +1,24 @@
+use super::SpawnDef;
+use serde::Deserialize;
+
+/// The random population for one layer of the map.
+#[derive(Clone, Deserialize)]
+pub struct SpawnTable {
+    pub layer: u32,
+    /// How many entries to spawn in total
+    pub total: i32,
+    /// Nothing spawns closer than this to the layer's starting point
+    pub min_distance: f32,
+    pub entries: Vec<SpawnEntry>,
+}
+
+#[derive(Clone, Deserialize)]
+pub struct SpawnEntry {
+    pub spawn: SpawnDef,
+    pub weight: i32,
+    /// Always spawned at least this many times
+    #[serde(default)]
+    pub min: i32,
+    #[serde(default)]
+    pub max: Option<i32>,
+}
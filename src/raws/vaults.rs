@@ -0,0 +1,54 @@
+use super::SpawnDef;
+use serde::Deserialize;
+
+// Vault drawings are embedded like everything else, keyed by file name.
+const VAULT_FILES: [(&str, &[u8]); 2] = [
+    (
+        "barricaded_lab.xp",
+        include_bytes!("../../resources/vaults/barricaded_lab.xp"),
+    ),
+    (
+        "queen_nest.xp",
+        include_bytes!("../../resources/vaults/queen_nest.xp"),
+    ),
+];
+
+pub fn vault_file(file: &str) -> Option<&'static [u8]> {
+    VAULT_FILES
+        .iter()
+        .find(|(name, _)| *name == file)
+        .map(|(_, bytes)| *bytes)
+}
+
+#[derive(Clone, Deserialize)]
+pub struct Vaults {
+    pub legend: Vec<LegendEntry>,
+    pub vaults: Vec<VaultDef>,
+}
+
+#[derive(Clone, Deserialize)]
+pub struct LegendEntry {
+    pub glyph: char,
+    pub tile: VaultTile,
+    #[serde(default)]
+    pub spawn: Option<SpawnDef>,
+}
+
+#[derive(Clone, Copy, Deserialize)]
+pub enum VaultTile {
+    Wall,
+    Floor,
+    Door,
+}
+
+#[derive(Clone, Deserialize)]
+pub struct VaultDef {
+    pub name: String,
+    pub file: String,
+    pub depths: Vec<u32>,
+    /// Percentage chance of trying to place the vault on each eligible layer
+    pub chance: i32,
+    /// Doors into the vault need a keycard
+    #[serde(default)]
+    pub locked: bool,
+}
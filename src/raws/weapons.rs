@@ -0,0 +1,77 @@
+use super::colour;
+use crate::components::{DamageType, Feed, FireMode, Weapon};
+use bracket_lib::prelude::to_cp437;
+use serde::Deserialize;
+
+#[derive(Clone, Deserialize)]
+pub struct Armoury {
+    /// Weapon names SecBot starts with, in the order they cycle
+    pub secbot: Vec<String>,
+    pub weapons: Vec<WeaponDef>,
+}
+
+#[derive(Clone, Deserialize)]
+pub struct WeaponDef {
+    pub name: String,
+    pub power: i32,
+    pub range: i32,
+    pub accuracy: i32,
+    #[serde(default)]
+    pub penetration: i32,
+    #[serde(default)]
+    pub damage_type: DamageType,
+    pub glyph: char,
+    pub colour: String,
+    pub mode: FireMode,
+    pub feed: FeedDef,
+    pub reload_turns: i32,
+}
+
+/// Weapons always start fully loaded and cold.
+#[derive(Clone, Deserialize)]
+pub enum FeedDef {
+    Magazine {
+        capacity: i32,
+        reserve: i32,
+    },
+    Heat {
+        limit: i32,
+        per_shot: i32,
+        cooling: i32,
+    },
+}
+
+impl WeaponDef {
+    pub fn to_weapon(&self) -> Weapon {
+        Weapon {
+            name: self.name.clone(),
+            power: self.power,
+            range: self.range,
+            accuracy: self.accuracy,
+            penetration: self.penetration,
+            damage_type: self.damage_type,
+            glyph: to_cp437(self.glyph),
+            color: colour(&self.colour),
+            mode: self.mode,
+            feed: match self.feed {
+                FeedDef::Magazine { capacity, reserve } => Feed::Magazine {
+                    capacity,
+                    loaded: capacity,
+                    reserve,
+                },
+                FeedDef::Heat {
+                    limit,
+                    per_shot,
+                    cooling,
+                } => Feed::Heat {
+                    limit,
+                    heat: 0,
+                    per_shot,
+                    cooling,
+                },
+            },
+            reload_turns: self.reload_turns,
+            reloading: 0,
+        }
+    }
+}
@@ -2,7 +2,7 @@ use super::{gui::safe_print_color, gui::TargetInfo};
 use crate::{
     components::*,
     map::{Map, HEIGHT, WIDTH},
-    LAYER_CHR, LAYER_DECOR, LAYER_MAP, LAYER_TEXT,
+    LAYER_CHR, LAYER_DECOR, LAYER_ITEMS, LAYER_MAP, LAYER_TEXT,
 };
 use bracket_lib::prelude::*;
 use legion::*;
@@ -68,11 +68,28 @@ impl Camera {
         batch.submit(0).expect("Error batching map");
     }
 
+    pub fn render_items(&self, map: &Map, ecs: &World) {
+        let mut batch = DrawBatch::new();
+        batch.target(LAYER_ITEMS);
+
+        let mut query = <(&Position, &Glyph, &Item)>::query();
+        query.for_each(ecs, |(pos, glyph, _)| {
+            if pos.layer == map.current_layer as u32 {
+                let idx = map.get_current().point2d_to_index(pos.pt);
+                if map.get_current().visible[idx] {
+                    batch.set(self.world_to_screen(pos.pt), glyph.color, glyph.glyph);
+                }
+            }
+        });
+
+        batch.submit(2000).expect("Error batching items");
+    }
+
     pub fn render_glyphs(&self, map: &Map, ecs: &World) {
         let mut batch = DrawBatch::new();
         batch.target(LAYER_CHR);
 
-        let mut query = <(&Position, &Glyph)>::query();
+        let mut query = <(&Position, &Glyph)>::query().filter(!component::<Item>());
         query.for_each(ecs, |(pos, glyph)| {
             if pos.layer == map.current_layer as u32 {
                 let idx = map.get_current().point2d_to_index(pos.pt);
@@ -147,6 +164,60 @@ impl Camera {
         }
     }
 
+    /// Shows where a thrown grenade would land and what the blast would reach.
+    pub fn render_throw_preview(&self, map: &Map, target: Point) {
+        let mut batch = DrawBatch::new();
+        batch.target(LAYER_DECOR);
+
+        match crate::game::inventory::throw_landing(map, self.player_pos, target) {
+            Some(landing) => {
+                line2d_bresenham(self.player_pos, landing)
+                    .iter()
+                    .skip(1)
+                    .for_each(|pt| {
+                        batch.set(
+                            self.world_to_screen(*pt),
+                            ColorPair::new(YELLOW, BLACK),
+                            to_cp437('∙'),
+                        );
+                    });
+                field_of_view(landing, crate::game::BOOM_RANGE, map.get_current())
+                    .iter()
+                    .for_each(|pt| {
+                        batch.set(
+                            self.world_to_screen(*pt),
+                            ColorPair::new(ORANGE, BLACK),
+                            to_cp437('░'),
+                        );
+                    });
+                batch.set(
+                    self.world_to_screen(landing),
+                    ColorPair::new(RED, BLACK),
+                    to_cp437('X'),
+                );
+            }
+            None => {
+                batch.set(
+                    self.world_to_screen(target),
+                    ColorPair::new(DARK_GRAY, BLACK),
+                    to_cp437('X'),
+                );
+            }
+        }
+
+        batch.submit(35_000).expect("Error batching throw preview");
+
+        let mut text = DrawBatch::new();
+        text.target(LAYER_TEXT);
+        safe_print_color(
+            &mut text,
+            Point::new(2, 1),
+            "Throw where? Move to aim, [Enter] to throw, [Esc] to cancel",
+            ColorPair::new(YELLOW, BLACK),
+        );
+        text.submit(35_000).expect("Error batching throw preview");
+    }
+
     pub fn render_projectiles(&self, ecs: &mut World, map: &Map) {
         let mut batch = DrawBatch::new();
         batch.target(LAYER_DECOR);
@@ -172,12 +243,12 @@ impl Camera {
 
     pub fn render_tooltips(
         &self,
-        ecs: &mut World,
+        ecs: &World,
         map: &Map,
         mouse_x: i32,
         mouse_y: i32,
         clicked: bool,
-    ) {
+    ) -> Option<Entity> {
         let mut batch = DrawBatch::new();
         batch.target(LAYER_TEXT);
         let map_pos = self.screen_to_world(mouse_x, mouse_y);
@@ -197,6 +268,23 @@ impl Camera {
                             if let Ok(hp) = er.get_component::<Health>() {
                                 lines.push((GRAY, format!("{}/{} hp", hp.current, hp.max)));
                             }
+                            if let Ok(shield) = er.get_component::<Shield>() {
+                                lines.push((
+                                    LIGHT_BLUE,
+                                    format!("{}/{} shield", shield.current, shield.max),
+                                ));
+                            }
+                            if let Ok(effects) = er.get_component::<StatusEffects>() {
+                                effects.0.iter().for_each(|e| {
+                                    lines.push((
+                                        super::gui::effect_color(e.kind),
+                                        format!("{} ({})", e.kind.name(), e.turns),
+                                    ));
+                                });
+                            }
+                            if let Ok(behaviour) = er.get_component::<Behaviour>() {
+                                lines.push((GRAY, behaviour.state.describe().to_string()));
+                            }
                         }
                         if clicked {
                             //println!("Set new target");
@@ -243,14 +331,8 @@ impl Camera {
 
         batch.submit(100_000).expect("Error batching tooltips");
 
-        if let Some(target) = new_target {
-            <(&Player, &mut Targeting)>::query().for_each_mut(ecs, |(_, t)| {
-                let tf = t.targets.iter().enumerate().find(|(_i, e)| e.0 == target);
-                if let Some((idx, _)) = tf {
-                    t.index = idx;
-                    t.current_target = Some(target);
-                }
-            });
-        }
+        // Selecting the target is a player command, so it's handed back rather
+        // than applied here.
+        new_target
     }
 }
@@ -0,0 +1,27 @@
+use super::safe_print_color;
+use crate::components::{StatusEffect, StatusKind};
+use crate::LAYER_TEXT;
+use bracket_lib::prelude::*;
+
+pub fn effect_color(kind: StatusKind) -> (u8, u8, u8) {
+    match kind {
+        StatusKind::Stunned => YELLOW,
+        StatusKind::Burning => ORANGE,
+        StatusKind::Corroded => LIME_GREEN,
+        StatusKind::Slowed => CYAN,
+        StatusKind::Blinded => MAGENTA,
+        StatusKind::Hasted => GOLD,
+    }
+}
+
+pub fn render_effects(batch: &mut DrawBatch, effects: &[StatusEffect]) {
+    batch.target(LAYER_TEXT); // Draw on the text layer
+    effects.iter().enumerate().for_each(|(i, effect)| {
+        safe_print_color(
+            batch,
+            Point::new(82, 25 + i as i32),
+            format!("{} ({} turns)", effect.kind.name(), effect.turns),
+            ColorPair::new(effect_color(effect.kind), BLACK),
+        );
+    });
+}
@@ -9,6 +9,10 @@ pub mod colony_info;
 pub use colony_info::*;
 pub mod targeting;
 pub use targeting::*;
+pub mod weapon;
+pub use weapon::*;
+pub mod effects;
+pub use effects::*;
 
 pub fn safe_print_color<T: ToString>(batch: &mut DrawBatch, pos: Point, text: T, color: ColorPair) {
     let len = text.to_string().len();
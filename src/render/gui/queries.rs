@@ -5,10 +5,14 @@ use legion::*;
 pub struct PlayerStatus {
     pub current_hp: i32,
     pub max_hp: i32,
+    /// Current and maximum shield, if SecBot has one
+    pub shield: Option<(i32, i32)>,
     pub property_damage: i32,
     pub human_resources: i32,
     pub colony: ColonyInfo,
     pub target: TargetInfo,
+    pub weapon: Option<Weapon>,
+    pub effects: Vec<StatusEffect>,
 }
 
 pub struct ColonyInfo {
@@ -33,19 +37,40 @@ impl PlayerStatus {
     pub fn query(ecs: &World, map_layer: usize) -> Self {
         let colony = PlayerStatus::colony_calculator(ecs, map_layer as u32);
         let (current_hp, max_hp) = PlayerStatus::health(ecs);
+        let shield = PlayerStatus::shield(ecs);
         let property_damage = PlayerStatus::property_damage(ecs);
         let human_resources = PlayerStatus::human_resources(&colony, property_damage);
         let target = PlayerStatus::targeting_info(ecs);
+        let weapon = PlayerStatus::weapon(ecs);
+        let effects = PlayerStatus::effects(ecs);
         Self {
             current_hp,
             max_hp,
+            shield,
             property_damage,
             human_resources,
             colony,
             target,
+            weapon,
+            effects,
         }
     }
 
+    fn weapon(ecs: &World) -> Option<Weapon> {
+        <(&Player, &Loadout)>::query()
+            .iter(ecs)
+            .map(|(_, l)| l.active().clone())
+            .nth(0)
+    }
+
+    fn effects(ecs: &World) -> Vec<StatusEffect> {
+        <(&Player, &StatusEffects)>::query()
+            .iter(ecs)
+            .map(|(_, s)| s.0.clone())
+            .nth(0)
+            .unwrap_or_default()
+    }
+
     fn health(ecs: &World) -> (i32, i32) {
         <(&Player, &Health)>::query()
             .iter(ecs)
@@ -54,6 +79,13 @@ impl PlayerStatus {
             .unwrap()
     }
 
+    fn shield(ecs: &World) -> Option<(i32, i32)> {
+        <(&Player, &Shield)>::query()
+            .iter(ecs)
+            .map(|(_, s)| (s.current, s.max))
+            .nth(0)
+    }
+
     fn property_damage(ecs: &World) -> i32 {
         <(&PropertyValue, &Position)>::query()
             .filter(!component::<Health>())
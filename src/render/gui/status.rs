@@ -1,22 +1,51 @@
 use super::queries::PlayerStatus;
 use super::safe_print_color;
+use crate::components::Feed;
 use crate::LAYER_TEXT;
 use bracket_lib::prelude::*;
 
 pub fn render_status(batch: &mut DrawBatch, status: &PlayerStatus) {
     batch.target(LAYER_TEXT); // Draw on the text layer
-    batch.bar_horizontal(
-        Point::new(82, 3),
-        29,
-        status.current_hp,
-        status.max_hp,
-        ColorPair::new(RED, DARK_RED),
-    );
-    batch.print_color_centered_at(
-        Point::new(97, 3),
-        format!("HP: {} / {}", status.current_hp, status.max_hp),
-        ColorPair::new(WHITE, RED),
-    );
+    if let Some((shield, max_shield)) = status.shield {
+        // Split the row between health and shield
+        batch.bar_horizontal(
+            Point::new(82, 3),
+            14,
+            status.current_hp,
+            status.max_hp,
+            ColorPair::new(RED, DARK_RED),
+        );
+        batch.print_color_centered_at(
+            Point::new(89, 3),
+            format!("HP: {}/{}", status.current_hp, status.max_hp),
+            ColorPair::new(WHITE, RED),
+        );
+        batch.bar_horizontal(
+            Point::new(97, 3),
+            14,
+            shield,
+            max_shield,
+            ColorPair::new(BLUE, DARK_BLUE),
+        );
+        batch.print_color_centered_at(
+            Point::new(104, 3),
+            format!("SH: {}/{}", shield, max_shield),
+            ColorPair::new(WHITE, BLUE),
+        );
+    } else {
+        batch.bar_horizontal(
+            Point::new(82, 3),
+            29,
+            status.current_hp,
+            status.max_hp,
+            ColorPair::new(RED, DARK_RED),
+        );
+        batch.print_color_centered_at(
+            Point::new(97, 3),
+            format!("HP: {} / {}", status.current_hp, status.max_hp),
+            ColorPair::new(WHITE, RED),
+        );
+    }
     safe_print_color(
         batch,
         Point::new(82, 4),
@@ -54,4 +83,45 @@ pub fn render_status(batch: &mut DrawBatch, status: &PlayerStatus) {
         phrase.to_string(),
         ColorPair::new(color, BLACK),
     );
+
+    if let Some(weapon) = &status.weapon {
+        let (current, max, text, color) = match weapon.feed {
+            Feed::Magazine {
+                capacity,
+                loaded,
+                reserve,
+            } => (
+                loaded,
+                capacity,
+                format!("Ammo: {} / {}  Spare: {}", loaded, capacity, reserve),
+                ColorPair::new(WHITE, DARK_GREEN),
+            ),
+            Feed::Heat { limit, heat, .. } => (
+                heat,
+                limit,
+                format!("Heat: {} / {}", heat, limit),
+                ColorPair::new(WHITE, DARK_ORANGE),
+            ),
+        };
+        let text = if weapon.reloading > 0 {
+            format!("Reloading ({})", weapon.reloading)
+        } else if weapon.is_dry() {
+            "OUT OF AMMO".to_string()
+        } else if !weapon.can_fire() {
+            match weapon.feed {
+                Feed::Magazine { .. } => "EMPTY - [R] to reload".to_string(),
+                Feed::Heat { .. } => "OVERHEATED - [R] to vent".to_string(),
+            }
+        } else {
+            text
+        };
+        batch.bar_horizontal(
+            Point::new(82, 7),
+            29,
+            current,
+            max,
+            ColorPair::new(color.bg, BLACK),
+        );
+        batch.print_color_centered_at(Point::new(97, 7), text, color);
+    }
 }
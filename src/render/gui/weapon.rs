@@ -0,0 +1,43 @@
+use super::safe_print_color;
+use crate::components::{FireMode, Weapon};
+use crate::LAYER_TEXT;
+use bracket_lib::prelude::*;
+
+pub fn render_weapon(batch: &mut DrawBatch, weapon: &Option<Weapon>) {
+    batch.target(LAYER_TEXT); // Draw on the text layer
+    if let Some(weapon) = weapon {
+        safe_print_color(
+            batch,
+            Point::new(82, 19),
+            format!("Weapon: {}", weapon.name),
+            ColorPair::new(weapon.color, BLACK),
+        );
+        let mode = match weapon.mode {
+            FireMode::Single => "Single shot".to_string(),
+            FireMode::Burst(n) => format!("Burst of {}", n),
+            FireMode::Spread(n) => format!("Spread of {}", n),
+        };
+        safe_print_color(
+            batch,
+            Point::new(82, 20),
+            format!("Power: {}  Range: {}", weapon.power, weapon.range),
+            ColorPair::new(GRAY, BLACK),
+        );
+        safe_print_color(
+            batch,
+            Point::new(82, 21),
+            format!(
+                "Accuracy: {}%  Pierce: {}",
+                weapon.accuracy, weapon.penetration
+            ),
+            ColorPair::new(GRAY, BLACK),
+        );
+        safe_print_color(batch, Point::new(82, 22), mode, ColorPair::new(GRAY, BLACK));
+        safe_print_color(
+            batch,
+            Point::new(82, 23),
+            "[Q] to switch weapons",
+            ColorPair::new(GOLD, BLACK),
+        );
+    }
+}
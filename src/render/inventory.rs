@@ -0,0 +1,67 @@
+use crate::components::*;
+use crate::turn::InventoryMode;
+use crate::LAYER_TEXT;
+use bracket_lib::prelude::*;
+use legion::*;
+
+pub enum MenuResult {
+    NoResponse,
+    Cancel,
+    Selected(usize),
+}
+
+/// Lists SecBot's inventory and waits for a letter to pick an item.
+pub fn inventory_menu(ctx: &mut BTerm, ecs: &World, mode: InventoryMode) -> MenuResult {
+    let items: Vec<Item> = <(&Player, &Inventory)>::query()
+        .iter(ecs)
+        .map(|(_, inventory)| inventory.items.clone())
+        .nth(0)
+        .unwrap_or_default();
+
+    let title = match mode {
+        InventoryMode::Use => "Use which item?",
+        InventoryMode::Drop => "Drop which item?",
+    };
+
+    let mut draw_batch = DrawBatch::new();
+    draw_batch.target(LAYER_TEXT);
+    let height = i32::max(1, items.len() as i32) + 3;
+    draw_batch.draw_double_box(
+        Rect::with_size(19, 8, 71, height),
+        ColorPair::new(CYAN, BLACK),
+    );
+    draw_batch.print_color_centered_at(Point::new(54, 8), title, ColorPair::new(YELLOW, BLACK));
+    if items.is_empty() {
+        draw_batch.print_color(
+            Point::new(21, 9),
+            "You aren't carrying anything.",
+            ColorPair::new(GRAY, BLACK),
+        );
+    }
+    for (i, item) in items.iter().enumerate() {
+        draw_batch.print_color(
+            Point::new(21, 9 + i as i32),
+            format!("({}) {}", (b'a' + i as u8) as char, item.name),
+            ColorPair::new(WHITE, BLACK),
+        );
+    }
+    draw_batch.print_color_centered_at(
+        Point::new(54, 8 + height),
+        "ESCAPE TO CANCEL",
+        ColorPair::new(YELLOW, BLACK),
+    );
+    draw_batch.submit(1_000_000).expect("Batch error");
+
+    match ctx.key {
+        Some(VirtualKeyCode::Escape) => MenuResult::Cancel,
+        Some(key) => {
+            let selection = letter_to_option(key);
+            if selection >= 0 && (selection as usize) < items.len() {
+                MenuResult::Selected(selection as usize)
+            } else {
+                MenuResult::NoResponse
+            }
+        }
+        None => MenuResult::NoResponse,
+    }
+}
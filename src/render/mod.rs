@@ -4,6 +4,8 @@ use legion::*;
 mod gui;
 pub mod modal;
 pub use modal::*;
+pub mod inventory;
+pub use inventory::*;
 mod camera;
 pub mod gameover;
 pub use gameover::*;
@@ -16,7 +18,16 @@ pub fn clear_all_consoles(ctx: &mut BTerm) {
     ctx.set_active_console(0);
 }
 
-pub fn render_gui(ecs: &mut World, map: &Map, mouse_x: i32, mouse_y: i32, clicked: bool) {
+/// Draws the game, returning the entity the player clicked on (if any).
+/// `throw_target` is the tile being aimed at while throwing something.
+pub fn render_gui(
+    ecs: &mut World,
+    map: &Map,
+    mouse_x: i32,
+    mouse_y: i32,
+    clicked: bool,
+    throw_target: Option<Point>,
+) -> Option<Entity> {
     let status = gui::PlayerStatus::query(ecs, map.current_layer);
 
     let camera = camera::Camera::new(ecs);
@@ -26,12 +37,18 @@ pub fn render_gui(ecs: &mut World, map: &Map, mouse_x: i32, mouse_y: i32, clicke
     gui::render_status(&mut gui_batch, &status);
     gui::render_colony_info(&mut gui_batch, &status.colony);
     gui::render_targeting(&mut gui_batch, &status.target);
+    gui::render_weapon(&mut gui_batch, &status.weapon);
+    gui::render_effects(&mut gui_batch, &status.effects);
     gui_batch.submit(50_000).expect("Batch error"); // On top of everything
 
     camera.render_map(map);
+    camera.render_items(map, ecs);
     camera.render_glyphs(map, ecs);
     camera.render_speech(ecs, map);
     camera.render_projectiles(ecs, map);
     camera.render_targeting(&status.target);
-    camera.render_tooltips(ecs, map, mouse_x, mouse_y, clicked);
+    if let Some(target) = throw_target {
+        camera.render_throw_preview(map, target);
+    }
+    camera.render_tooltips(ecs, map, mouse_x, mouse_y, clicked)
 }
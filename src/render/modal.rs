@@ -3,6 +3,36 @@ use crate::LAYER_TEXT;
 use bracket_lib::prelude::*;
 
 pub fn modal(ctx: &mut BTerm, title: &String, body: &String) -> NewState {
+    draw_modal(title, body, "PRESS ENTER TO CONTINUE");
+
+    if let Some(key) = ctx.key {
+        match key {
+            VirtualKeyCode::Return => NewState::Wait,
+            VirtualKeyCode::Space => NewState::Wait,
+            _ => NewState::NoChange,
+        }
+    } else {
+        NewState::NoChange
+    }
+}
+
+/// Saving failed, so let the player decide whether to keep going or quit
+/// without a save.
+pub fn save_failed(ctx: &mut BTerm, error: &str) -> NewState {
+    draw_modal(
+        "Couldn't Save",
+        &format!("Couldn't save: {}", error),
+        "ENTER TO KEEP PLAYING, Q TO QUIT ANYWAY",
+    );
+
+    match ctx.key {
+        Some(VirtualKeyCode::Return) | Some(VirtualKeyCode::Space) => NewState::Wait,
+        Some(VirtualKeyCode::Q) => NewState::Quit,
+        _ => NewState::NoChange,
+    }
+}
+
+fn draw_modal(title: &str, body: &str, prompt: &str) {
     let mut draw_batch = DrawBatch::new();
     draw_batch.target(LAYER_TEXT);
     draw_batch.draw_double_box(Rect::with_size(19, 8, 71, 12), ColorPair::new(CYAN, BLACK));
@@ -20,21 +50,11 @@ pub fn modal(ctx: &mut BTerm, title: &String, body: &String) -> NewState {
         .ln()
         .fg(YELLOW)
         .bg(BLACK)
-        .centered("PRESS ENTER TO CONTINUE")
+        .centered(prompt)
         .reset();
 
     let mut block = TextBlock::new(21, 9, 69, 11);
     block.print(&buf).expect("Overflow occurred");
     block.render_to_draw_batch(&mut draw_batch);
     draw_batch.submit(1_000_000).expect("Batch error");
-
-    if let Some(key) = ctx.key {
-        match key {
-            VirtualKeyCode::Return => NewState::Wait,
-            VirtualKeyCode::Space => NewState::Wait,
-            _ => NewState::NoChange,
-        }
-    } else {
-        NewState::NoChange
-    }
 }
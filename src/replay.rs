@@ -0,0 +1,115 @@
+use crate::simulation::{Command, Simulation};
+use bracket_lib::prelude::VirtualKeyCode;
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+
+const RECORDING_VERSION: u32 = crate::save::SAVE_VERSION;
+pub const RECORDING_FILE: &str = "last_run.replay";
+
+/// A seed plus every command the player issued. Played back through the
+/// turn machine, it reproduces the run exactly.
+#[derive(Serialize, Deserialize)]
+pub struct Recording {
+    pub version: u32,
+    pub seed: u64,
+    pub commands: Vec<Command>,
+}
+
+impl Recording {
+    pub fn from_simulation(sim: &Simulation) -> Self {
+        Self {
+            version: RECORDING_VERSION,
+            seed: sim.seed,
+            commands: sim.commands.clone(),
+        }
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), Box<dyn Error>> {
+        std::fs::write(path, ron::ser::to_string(self)?)?;
+        Ok(())
+    }
+
+    pub fn load(path: &str) -> Result<Self, Box<dyn Error>> {
+        let recording: Recording = ron::de::from_str(&std::fs::read_to_string(path)?)?;
+        if recording.version != RECORDING_VERSION {
+            return Err(format!(
+                "Recording is version {}, expected {}",
+                recording.version, RECORDING_VERSION
+            )
+            .into());
+        }
+        Ok(recording)
+    }
+}
+
+pub enum ReplaySpeed {
+    /// Wait for a key press before each command
+    Step,
+    /// Issue a command every `n` frames
+    FramesPerCommand(u32),
+}
+
+pub struct Replay {
+    recording: Recording,
+    next: usize,
+    speed: ReplaySpeed,
+    countdown: u32,
+}
+
+impl Replay {
+    pub fn new(recording: Recording, speed: ReplaySpeed) -> Self {
+        Self {
+            recording,
+            next: 0,
+            speed,
+            countdown: 0,
+        }
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.recording.seed
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.next >= self.recording.commands.len()
+    }
+
+    /// Called once per frame while the game waits for input. Returns the next
+    /// recorded command once it's time to play it.
+    pub fn next_command(&mut self, key: Option<VirtualKeyCode>) -> Option<Command> {
+        if self.is_finished() {
+            return None;
+        }
+        let ready = match self.speed {
+            ReplaySpeed::Step => matches!(
+                key,
+                Some(VirtualKeyCode::Space)
+                    | Some(VirtualKeyCode::Return)
+                    | Some(VirtualKeyCode::Right)
+            ),
+            ReplaySpeed::FramesPerCommand(frames) => {
+                if self.countdown == 0 {
+                    self.countdown = frames;
+                    true
+                } else {
+                    self.countdown -= 1;
+                    false
+                }
+            }
+        };
+        if ready {
+            self.next += 1;
+            Some(self.recording.commands[self.next - 1])
+        } else {
+            None
+        }
+    }
+
+    /// Plays every remaining command without rendering.
+    pub fn run_headless(&mut self, sim: &mut Simulation) {
+        while !self.is_finished() && !sim.is_game_over() {
+            sim.play_turn(self.recording.commands[self.next]);
+            self.next += 1;
+        }
+    }
+}
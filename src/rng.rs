@@ -2,6 +2,17 @@ use bracket_lib::prelude::RandomNumberGenerator;
 use lazy_static::*;
 use std::sync::Mutex;
 
+// Every random roll in the game - map generation, combat and AI - comes from
+// here, so a run can be reproduced by starting from the same seed.
 lazy_static! {
     pub static ref RNG: Mutex<RandomNumberGenerator> = Mutex::new(RandomNumberGenerator::new());
 }
+
+pub fn reseed(seed: u64) {
+    *RNG.lock().unwrap() = RandomNumberGenerator::seeded(seed);
+}
+
+/// Picks a seed from entropy, for runs that didn't ask for a specific one.
+pub fn random_seed() -> u64 {
+    RandomNumberGenerator::new().next_u64()
+}
@@ -0,0 +1,352 @@
+use crate::{
+    components::*,
+    map::Map,
+    simulation::Simulation,
+    stats::PlayStats,
+    turn::TurnState,
+};
+use legion::storage::Component;
+use legion::world::EntryRef;
+use legion::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::error::Error;
+
+// Bump this whenever the layout of `SaveGame` changes; older saves are refused.
+// Recordings share it, since the same changes usually alter the game rules.
+pub const SAVE_VERSION: u32 = 16;
+pub const SAVE_FILE: &str = "secbot.sav";
+
+#[derive(Serialize, Deserialize)]
+struct SaveGame {
+    version: u32,
+    seed: u64,
+    stats: PlayStats,
+    turn: TurnState,
+    map: Map,
+    entities: Vec<SavedEntity>,
+}
+
+// Entity references are stored as indices into `SaveGame::entities`.
+#[derive(Serialize, Deserialize)]
+struct SavedTargeting {
+    targets: Vec<(usize, f32)>,
+    current_target: Option<usize>,
+    index: usize,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SavedEntity {
+    position: Option<Position>,
+    behaviour: Option<Behaviour>,
+    name: Option<Name>,
+    description: Option<Description>,
+    glyph: Option<Glyph>,
+    health: Option<Health>,
+    blood: Option<Blood>,
+    colonist: Option<Colonist>,
+    colonist_status: Option<ColonistStatus>,
+    dialog: Option<Dialog>,
+    energy: Option<Energy>,
+    explosive: Option<Explosive>,
+    boom: Option<Boom>,
+    fov: Option<FieldOfView>,
+    grudge: Option<Vec<(usize, i32)>>,
+    hostile: Option<Hostile>,
+    inventory: Option<Inventory>,
+    item: Option<Item>,
+    loadout: Option<Loadout>,
+    melee: Option<Melee>,
+    property_value: Option<PropertyValue>,
+    resistances: Option<Resistances>,
+    shield: Option<Shield>,
+    speech: Option<Speech>,
+    status_effects: Option<StatusEffects>,
+    targeting: Option<SavedTargeting>,
+    tile_trigger: Option<TileTrigger>,
+    timed_event: Option<TimedEvent>,
+    player: bool,
+    door: bool,
+    locked: bool,
+    targetable: bool,
+    active: bool,
+    can_be_activated: bool,
+    found: bool,
+    set_decoration: bool,
+    friendly: bool,
+}
+
+fn cloned<T: Component + Clone>(entry: &EntryRef) -> Option<T> {
+    entry.get_component::<T>().ok().cloned()
+}
+
+fn has<T: Component>(entry: &EntryRef) -> bool {
+    entry.get_component::<T>().is_ok()
+}
+
+pub fn save_exists() -> bool {
+    std::path::Path::new(SAVE_FILE).exists()
+}
+
+pub fn save_game(sim: &Simulation) -> Result<(), Box<dyn Error>> {
+    // Projectiles are purely cosmetic and have no position, so they aren't saved.
+    let entities: Vec<Entity> = <(Entity, &Position)>::query()
+        .iter(&sim.ecs)
+        .map(|(e, _)| *e)
+        .collect();
+    let index: HashMap<Entity, usize> = entities.iter().enumerate().map(|(i, e)| (*e, i)).collect();
+
+    let saved_entities = entities
+        .iter()
+        .map(|entity| {
+            let entry = sim.ecs.entry_ref(*entity).unwrap();
+            SavedEntity {
+                position: cloned(&entry),
+                behaviour: cloned(&entry),
+                name: cloned(&entry),
+                description: cloned(&entry),
+                glyph: cloned(&entry),
+                health: cloned(&entry),
+                blood: cloned(&entry),
+                colonist: cloned(&entry),
+                colonist_status: cloned(&entry),
+                dialog: cloned(&entry),
+                energy: cloned(&entry),
+                explosive: cloned(&entry),
+                boom: cloned(&entry),
+                fov: cloned(&entry),
+                grudge: entry.get_component::<Grudge>().ok().map(|g| {
+                    g.0.iter()
+                        .filter_map(|(e, damage)| index.get(e).map(|i| (*i, *damage)))
+                        .collect()
+                }),
+                hostile: cloned(&entry),
+                inventory: cloned(&entry),
+                item: cloned(&entry),
+                loadout: cloned(&entry),
+                melee: cloned(&entry),
+                property_value: cloned(&entry),
+                resistances: cloned(&entry),
+                shield: cloned(&entry),
+                speech: cloned(&entry),
+                status_effects: cloned(&entry),
+                targeting: entry
+                    .get_component::<Targeting>()
+                    .ok()
+                    .map(|t| SavedTargeting {
+                        targets: t
+                            .targets
+                            .iter()
+                            .filter_map(|(e, d)| index.get(e).map(|i| (*i, *d)))
+                            .collect(),
+                        current_target: t.current_target.and_then(|e| index.get(&e).copied()),
+                        index: t.index,
+                    }),
+                tile_trigger: cloned(&entry),
+                timed_event: cloned(&entry),
+                player: has::<Player>(&entry),
+                door: has::<Door>(&entry),
+                locked: has::<Locked>(&entry),
+                targetable: has::<Targetable>(&entry),
+                active: has::<Active>(&entry),
+                can_be_activated: has::<CanBeActivated>(&entry),
+                found: has::<Found>(&entry),
+                set_decoration: has::<SetDecoration>(&entry),
+                friendly: has::<Friendly>(&entry),
+            }
+        })
+        .collect();
+
+    let save = SaveGame {
+        version: SAVE_VERSION,
+        seed: sim.seed,
+        stats: crate::stats::get_stats(),
+        turn: resume_turn(&sim.turn),
+        map: sim.map.clone(),
+        entities: saved_entities,
+    };
+    std::fs::write(SAVE_FILE, ron::ser::to_string(&save)?)?;
+    Ok(())
+}
+
+fn resume_turn(turn: &TurnState) -> TurnState {
+    // Saving mid-turn would desynchronize the turn machine, so always resume
+    // from a clean input prompt.
+    match turn {
+        TurnState::Modal { title, body } => TurnState::Modal {
+            title: title.clone(),
+            body: body.clone(),
+        },
+        _ => TurnState::WaitingForInput,
+    }
+}
+
+pub fn load_game() -> Result<Simulation, Box<dyn Error>> {
+    let save: SaveGame = ron::de::from_str(&std::fs::read_to_string(SAVE_FILE)?)?;
+    if save.version != SAVE_VERSION {
+        return Err(format!(
+            "Save file is version {}, expected {}",
+            save.version, SAVE_VERSION
+        )
+        .into());
+    }
+
+    let mut ecs = World::default();
+    let mut entities = Vec::with_capacity(save.entities.len());
+    let mut targeting = Vec::new();
+    let mut grudges = Vec::new();
+    for saved in save.entities {
+        let e = ecs.push((saved.position.unwrap(),));
+        let mut entry = ecs.entry(e).unwrap();
+        if let Some(c) = saved.behaviour {
+            entry.add_component(c);
+        }
+        if let Some(c) = saved.name {
+            entry.add_component(c);
+        }
+        if let Some(c) = saved.description {
+            entry.add_component(c);
+        }
+        if let Some(c) = saved.glyph {
+            entry.add_component(c);
+        }
+        if let Some(c) = saved.health {
+            entry.add_component(c);
+        }
+        if let Some(c) = saved.blood {
+            entry.add_component(c);
+        }
+        if let Some(c) = saved.colonist {
+            entry.add_component(c);
+        }
+        if let Some(c) = saved.colonist_status {
+            entry.add_component(c);
+        }
+        if let Some(c) = saved.dialog {
+            entry.add_component(c);
+        }
+        if let Some(c) = saved.energy {
+            entry.add_component(c);
+        }
+        if let Some(c) = saved.explosive {
+            entry.add_component(c);
+        }
+        if let Some(c) = saved.boom {
+            entry.add_component(c);
+        }
+        if let Some(c) = saved.fov {
+            entry.add_component(c);
+        }
+        if let Some(c) = saved.hostile {
+            entry.add_component(c);
+        }
+        if let Some(c) = saved.inventory {
+            entry.add_component(c);
+        }
+        if let Some(c) = saved.item {
+            entry.add_component(c);
+        }
+        if let Some(c) = saved.loadout {
+            entry.add_component(c);
+        }
+        if let Some(c) = saved.melee {
+            entry.add_component(c);
+        }
+        if let Some(c) = saved.property_value {
+            entry.add_component(c);
+        }
+        if let Some(c) = saved.resistances {
+            entry.add_component(c);
+        }
+        if let Some(c) = saved.shield {
+            entry.add_component(c);
+        }
+        if let Some(c) = saved.speech {
+            entry.add_component(c);
+        }
+        if let Some(c) = saved.status_effects {
+            entry.add_component(c);
+        }
+        if let Some(c) = saved.tile_trigger {
+            entry.add_component(c);
+        }
+        if let Some(c) = saved.timed_event {
+            entry.add_component(c);
+        }
+        if saved.player {
+            entry.add_component(Player {});
+        }
+        if saved.door {
+            entry.add_component(Door {});
+        }
+        if saved.locked {
+            entry.add_component(Locked {});
+        }
+        if saved.targetable {
+            entry.add_component(Targetable {});
+        }
+        if saved.active {
+            entry.add_component(Active {});
+        }
+        if saved.can_be_activated {
+            entry.add_component(CanBeActivated {});
+        }
+        if saved.found {
+            entry.add_component(Found {});
+        }
+        if saved.set_decoration {
+            entry.add_component(SetDecoration {});
+        }
+        if saved.friendly {
+            entry.add_component(Friendly {});
+        }
+        if let Some(t) = saved.targeting {
+            targeting.push((e, t));
+        }
+        if let Some(g) = saved.grudge {
+            grudges.push((e, g));
+        }
+        entities.push(e);
+    }
+
+    // Entity references can only be resolved once everything exists
+    for (e, t) in targeting {
+        if let Some(mut entry) = ecs.entry(e) {
+            entry.add_component(Targeting {
+                targets: t.targets.iter().map(|(i, d)| (entities[*i], *d)).collect(),
+                current_target: t.current_target.map(|i| entities[i]),
+                index: t.index,
+            });
+        }
+    }
+
+    for (e, g) in grudges {
+        if let Some(mut entry) = ecs.entry(e) {
+            entry.add_component(Grudge(
+                g.iter()
+                    .map(|(i, damage)| (entities[*i], *damage))
+                    .collect(),
+            ));
+        }
+    }
+
+    // Loading gives every entity a new id and rebuilds archetypes in a new
+    // order, and both decide the order queries (and so random rolls) run in.
+    // A resumed game can't carry on the exact sequence it would have had, so
+    // it starts a new random sequence instead.
+    crate::rng::reseed(crate::rng::random_seed());
+    crate::stats::restore(save.stats);
+
+    Ok(Simulation {
+        seed: save.seed,
+        ecs,
+        map: save.map,
+        turn: save.turn,
+        commands: Vec::new(),
+        resumed: true,
+    })
+}
+
+pub fn delete_save() {
+    let _ = std::fs::remove_file(SAVE_FILE);
+}
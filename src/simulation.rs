@@ -0,0 +1,315 @@
+use crate::{
+    components::*,
+    game::{
+        self,
+        energy::{Actor, Queue},
+        targets::Targets,
+    },
+    map::Map,
+    raws::RAWS,
+    turn::{GameOverType, TurnState},
+    NewState,
+};
+use bracket_lib::prelude::*;
+use legion::systems::CommandBuffer;
+use legion::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// Everything the player can ask SecBot to do, independent of how it was asked.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Command {
+    Move { delta_x: i32, delta_y: i32 },
+    Fire,
+    CycleTarget,
+    SelectTarget(usize),
+    CycleWeapon,
+    Reload,
+    PickUp,
+    UseItem(usize),
+    DropItem(usize),
+    Throw { item: usize, target: Point },
+    GoUp,
+    GoDown,
+    Wait,
+}
+
+/// The game world and turn machine, with no dependency on a window or keyboard.
+/// The renderer and input handling in `turn::State` sit on top of this.
+pub struct Simulation {
+    pub seed: u64,
+    pub ecs: World,
+    pub map: Map,
+    pub turn: TurnState,
+    /// Every command issued since the game began, for replays.
+    pub commands: Vec<Command>,
+    /// Loaded from a save, so the seed and commands no longer reproduce it.
+    pub resumed: bool,
+}
+
+impl Simulation {
+    /// Starts a new game from a given seed. The same seed and the same
+    /// sequence of commands always produce the same game.
+    pub fn new(seed: u64) -> Self {
+        crate::stats::reset();
+        crate::rng::reseed(seed);
+        let mut ecs = World::default();
+        let map = Map::new(&mut ecs);
+        let mut sim = Self {
+            seed,
+            ecs,
+            map,
+            turn: TurnState::WaitingForInput,
+            commands: Vec::new(),
+            resumed: false,
+        };
+        sim.spawn_player();
+        sim
+    }
+
+    fn spawn_player(&mut self) {
+        let e = self.ecs.push((
+            Player {},
+            Name("SecBot".to_string()),
+            Position::with_pt(
+                self.map.get_current().starting_point,
+                self.map.current_layer as u32,
+            ),
+            Glyph {
+                glyph: to_cp437('@'),
+                color: ColorPair::new(YELLOW, BLACK),
+            },
+            Description("Everybody's favorite Bracket Corp SecBot".to_string()),
+            FieldOfView {
+                radius: 20,
+                visible_tiles: HashSet::new(),
+            },
+            Targeting {
+                targets: Vec::new(),
+                current_target: None,
+                index: 0,
+            },
+            Health {
+                max: 10,
+                current: 10,
+            },
+        ));
+        let mut commands = CommandBuffer::new(&self.ecs);
+        commands.add_component(e, Blood(BROWN2.into()));
+        commands.add_component(e, Inventory { items: Vec::new() });
+        commands.add_component(e, Energy::new(100));
+        // Xenomorph acid eats through SecBot's casing
+        commands.add_component(e, Resistances(vec![(DamageType::Acid, 150)]));
+        commands.add_component(
+            e,
+            Shield {
+                max: 6,
+                current: 6,
+                recharge: 1,
+                delay: 3,
+                cooldown: 0,
+            },
+        );
+        commands.add_component(
+            e,
+            Melee {
+                damage: 3,
+                damage_type: DamageType::Kinetic,
+                inflicts: None,
+            },
+        );
+        commands.add_component(
+            e,
+            Loadout {
+                weapons: RAWS
+                    .weapons
+                    .secbot
+                    .iter()
+                    .filter_map(|name| RAWS.weapon(name))
+                    .map(|w| w.to_weapon())
+                    .collect(),
+                current: 0,
+            },
+        );
+        commands.flush(&mut self.ecs);
+
+        // Trigger FOV for the first round
+        game::player::update_fov(&NewState::Enemy, &mut self.ecs, &mut self.map);
+    }
+
+    pub fn is_waiting_for_input(&self) -> bool {
+        matches!(self.turn, TurnState::WaitingForInput)
+    }
+
+    pub fn is_game_over(&self) -> bool {
+        matches!(self.turn, TurnState::GameOver { .. })
+    }
+
+    /// Performs a player command. Only meaningful while waiting for input; the
+    /// returned state still needs to be passed to `apply`.
+    pub fn player_command(&mut self, command: Command) -> NewState {
+        if !self.is_waiting_for_input() {
+            return NewState::NoChange;
+        }
+        self.commands.push(command);
+        game::player::perform_command(command, &mut self.ecs, &mut self.map)
+    }
+
+    fn player_dead(&self) -> bool {
+        <(&Player, &Health)>::query()
+            .iter(&self.ecs)
+            .any(|(_, hp)| hp.current == 0)
+    }
+
+    /// Runs a single non-interactive phase of the turn machine.
+    pub fn step(&mut self) -> NewState {
+        match &self.turn {
+            TurnState::PlayerTurn => {
+                if self.player_dead() {
+                    NewState::Dead
+                } else {
+                    NewState::Enemy
+                }
+            }
+            TurnState::EnemyTurn => {
+                // Actors take one action at a time, whoever has the most
+                // energy first, until it's SecBot's go or nobody can act.
+                let mut queue = Queue::new(&self.ecs);
+                let targets = Targets::gather(&self.ecs);
+                loop {
+                    match queue.next_actor(&self.ecs) {
+                        None => break NewState::WrapUp,
+                        Some(Actor::Player) if self.player_dead() => break NewState::Dead,
+                        Some(Actor::Player) => break NewState::Wait,
+                        Some(Actor::Friendly(e)) => {
+                            game::friendly_turn(&mut self.ecs, &mut self.map, e, &targets)
+                        }
+                        Some(Actor::Colonist(e)) => {
+                            game::colonist_turn(&mut self.ecs, &mut self.map, e, &targets)
+                        }
+                        Some(Actor::Monster(e)) => {
+                            game::monster_turn(&mut self.ecs, &mut self.map, e, &targets)
+                        }
+                    }
+                }
+            }
+            TurnState::WrapUpTurn => {
+                game::energy::regain_energy(&mut self.ecs);
+                game::combat::tick_weapons(&mut self.ecs);
+                game::combat::tick_shields(&mut self.ecs);
+                game::combat::tick_grudges(&mut self.ecs);
+                game::status_effects::tick_status_effects(&mut self.ecs);
+                game::timed_events::manage_event_timers(&mut self.ecs, &self.map);
+                game::explosions::process_explosions(&mut self.ecs, &mut self.map);
+                game::dialog::spawn_dialog(&mut self.ecs);
+                match game::turn_check::end_of_turn(&mut self.ecs) {
+                    // Back to the queue, which decides whether SecBot goes next
+                    NewState::Wait => {
+                        if !game::energy::player_ready(&self.ecs) {
+                            game::status_effects::announce_lost_turn(&mut self.ecs);
+                        }
+                        NewState::Enemy
+                    }
+                    new_state => new_state,
+                }
+            }
+            _ => NewState::NoChange,
+        }
+    }
+
+    /// Moves the turn machine along. Interface-only states (help, restart) are
+    /// left for the caller to handle.
+    pub fn apply(&mut self, new_state: &NewState) {
+        match new_state {
+            NewState::Wait => self.turn = TurnState::WaitingForInput,
+            NewState::Enemy => self.turn = TurnState::EnemyTurn,
+            NewState::WrapUp => self.turn = TurnState::WrapUpTurn,
+            NewState::Player => self.turn = TurnState::PlayerTurn,
+            NewState::LeftMap => {
+                self.turn = TurnState::GameOver {
+                    reason: GameOverType::Left,
+                }
+            }
+            NewState::Dead => {
+                self.turn = TurnState::GameOver {
+                    reason: GameOverType::Dead,
+                }
+            }
+            NewState::NoChange
+            | NewState::Restart
+            | NewState::Help
+            | NewState::Inventory
+            | NewState::DropMenu
+            | NewState::SaveAndQuit
+            | NewState::Quit => {}
+        }
+    }
+
+    /// Headless helper: performs a command and then runs every phase until
+    /// the player is asked for input again (or the game ends).
+    pub fn play_turn(&mut self, command: Command) {
+        let new_state = self.player_command(command);
+        self.apply(&new_state);
+        while matches!(
+            self.turn,
+            TurnState::PlayerTurn | TurnState::EnemyTurn | TurnState::WrapUpTurn
+        ) {
+            let new_state = self.step();
+            self.apply(&new_state);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stats::PlayStats;
+
+    /// Plays a short scripted game and reports where SecBot ended up.
+    fn play(seed: u64) -> (Position, i32, PlayStats) {
+        let script = [
+            Command::Move {
+                delta_x: 1,
+                delta_y: 0,
+            },
+            Command::Move {
+                delta_x: 0,
+                delta_y: 1,
+            },
+            Command::CycleTarget,
+            Command::Fire,
+            Command::Reload,
+            Command::Move {
+                delta_x: -1,
+                delta_y: 0,
+            },
+            Command::Move {
+                delta_x: 0,
+                delta_y: -1,
+            },
+            Command::PickUp,
+            Command::Wait,
+        ];
+        let mut sim = Simulation::new(seed);
+        for command in script.iter().cycle().take(90) {
+            if sim.is_game_over() {
+                break;
+            }
+            sim.play_turn(*command);
+        }
+        let (pos, hp) = <(&Player, &Position, &Health)>::query()
+            .iter(&sim.ecs)
+            .map(|(_, pos, hp)| (*pos, hp.current))
+            .nth(0)
+            .unwrap();
+        (pos, hp, crate::stats::get_stats())
+    }
+
+    #[test]
+    fn same_seed_plays_the_same_game() {
+        let first = play(1234);
+        let second = play(1234);
+        assert_eq!(first, second);
+        assert!(first.2.turns_elapsed > 0);
+    }
+}
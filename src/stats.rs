@@ -1,11 +1,12 @@
 use lazy_static::*;
+use serde::{Deserialize, Serialize};
 use std::sync::Mutex;
 
 lazy_static! {
     static ref STATS: Mutex<PlayStats> = Mutex::new(PlayStats::new());
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct PlayStats {
     pub turns_elapsed: usize,
     pub last_heard: String,
@@ -29,7 +30,7 @@ impl PlayStats {
 pub fn reset() {
     let mut lock = STATS.lock();
     let stats = lock.as_mut().unwrap();
-    stats.turns_elapsed = 0;
+    *stats = PlayStats::new();
 }
 
 pub fn record_turn() {
@@ -67,3 +68,9 @@ pub fn get_stats() -> PlayStats {
     let stats = lock.as_mut().unwrap();
     stats.clone()
 }
+
+pub fn restore(saved: PlayStats) {
+    let mut lock = STATS.lock();
+    let stats = lock.as_mut().unwrap();
+    *stats = saved;
+}
@@ -1 +1,3 @@
 pub const INTRO : &str = "As Bracket Corp's #1 troubleshooting security bot - a bio-mechanical mishmash of bits of dead person and robotics - you have been sent to Bracket 394 to find out why the colonists aren't responding. You can win the game by accounting for - and ideally saving - the colonists.";
+pub const RESUME : &str = "SecBot's systems come back online exactly where they left off. The colonists are still waiting.";
+pub const REPLAY_DONE: &str = "The recording ends here. You are now in control of SecBot.";
@@ -1,21 +1,41 @@
-use crate::{components::*, game, map::Map, render, text};
+use crate::{
+    game, render,
+    replay::Recording,
+    replay::Replay,
+    save,
+    simulation::{Command, Simulation},
+    text,
+};
+use crate::components::{Player, Position, Targeting};
 use bracket_lib::prelude::*;
-use legion::systems::CommandBuffer;
 use legion::*;
-use std::collections::HashSet;
+use serde::{Deserialize, Serialize};
 
+#[derive(Serialize, Deserialize)]
 pub enum GameOverType {
     Dead,
     Left,
 }
 
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub enum InventoryMode {
+    Use,
+    Drop,
+}
+
+#[derive(Serialize, Deserialize)]
 pub enum TurnState {
     WaitingForInput,
     PlayerTurn,
     EnemyTurn,
     WrapUpTurn,
     Modal { title: String, body: String },
+    Inventory { mode: InventoryMode },
+    /// Picking a tile to throw inventory item `item` at
+    Throwing { item: usize, target: Point },
     GameOver { reason: GameOverType },
+    /// ESC couldn't write the save file
+    SaveFailed { error: String },
 }
 
 #[derive(PartialEq)]
@@ -29,81 +49,120 @@ pub enum NewState {
     Dead,
     Restart,
     Help,
+    Inventory,
+    DropMenu,
+    SaveAndQuit,
+    Quit,
 }
 
 pub struct State {
-    pub ecs: World,
-    pub map: Map,
-    pub turn: TurnState,
+    pub sim: Simulation,
+    pub replay: Option<Replay>,
 }
 
 impl State {
-    pub fn new() -> Self {
-        crate::stats::reset();
-        let mut ecs = World::default();
-        let map = Map::new(&mut ecs);
+    pub fn new(seed: Option<u64>, replay: Option<Replay>) -> Self {
+        // Replays start straight into the recorded game
+        if let Some(replay) = replay {
+            return Self {
+                sim: Simulation::new(replay.seed()),
+                replay: Some(replay),
+            };
+        }
+
+        // Resume a saved run unless a specific seed was requested. The save is
+        // consumed on load, so a run can't be resumed twice.
+        if seed.is_none() && save::save_exists() {
+            if let Ok(sim) = save::load_game() {
+                save::delete_save();
+                let mut state = Self { sim, replay: None };
+                state.sim.turn = TurnState::Modal {
+                    title: "SecBot Reactivated".to_string(),
+                    body: text::RESUME.to_string(),
+                };
+                return state;
+            }
+        }
+
         let mut state = Self {
-            ecs,
-            map,
-            turn: TurnState::Modal {
-                title: "SecBot Has Landed".to_string(),
-                body: text::INTRO.to_string(),
-            },
+            sim: Simulation::new(seed.unwrap_or_else(crate::rng::random_seed)),
+            replay: None,
         };
-        state.new_game();
+        state.sim.turn = State::intro();
         state
     }
 
-    fn restart_game(&mut self) -> TurnState {
-        crate::stats::reset();
-        let mut ecs = World::default();
-        let map = Map::new(&mut ecs);
-        self.ecs = ecs;
-        self.map = map;
-        self.new_game();
-
-        // Restart with the modal
+    fn intro() -> TurnState {
         TurnState::Modal {
             title: "SecBot Has Landed".to_string(),
             body: text::INTRO.to_string(),
         }
     }
 
-    fn new_game(&mut self) {
-        // Spawn the player
-        let e = self.ecs.push((
-            Player {},
-            Name("SecBot".to_string()),
-            Position::with_pt(
-                self.map.get_current().starting_point,
-                self.map.current_layer as u32,
-            ),
-            Glyph {
-                glyph: to_cp437('@'),
-                color: ColorPair::new(YELLOW, BLACK),
-            },
-            Description("Everybody's favorite Bracket Corp SecBot".to_string()),
-            FieldOfView {
-                radius: 20,
-                visible_tiles: HashSet::new(),
-            },
-            Targeting {
-                targets: Vec::new(),
-                current_target: None,
-                index: 0,
-            },
-            Health {
-                max: 10,
-                current: 10,
-            },
-        ));
-        let mut commands = CommandBuffer::new(&self.ecs);
-        commands.add_component(e, Blood(BROWN2.into()));
-        commands.flush(&mut self.ecs);
-        // TODO: Add blood
-
-        // Trigger FOV for the first round
-        game::player::update_fov(&NewState::Enemy, &mut self.ecs, &mut self.map);
+    fn restart_game(&mut self) {
+        self.sim = Simulation::new(crate::rng::random_seed());
+        self.replay = None;
+
+        // Restart with the modal
+        self.sim.turn = State::intro();
+    }
+
+    // Aim at the current target if it's within reach, otherwise at SecBot's
+    // feet
+    fn throw_start(&self) -> Point {
+        <(&Player, &Position, &Targeting)>::query()
+            .iter(&self.sim.ecs)
+            .map(|(_, pos, targeting)| {
+                targeting
+                    .current_target
+                    .and_then(|t| self.sim.ecs.entry_ref(t).ok())
+                    .and_then(|e| e.get_component::<Position>().ok().map(|p| p.pt))
+                    .filter(|pt| self.throw_in_reach(*pt))
+                    .unwrap_or(pos.pt)
+            })
+            .nth(0)
+            .unwrap()
+    }
+
+    // The aiming cursor stays on the map and within throwing range of SecBot
+    fn throw_in_reach(&self, target: Point) -> bool {
+        <(&Player, &Position)>::query()
+            .iter(&self.sim.ecs)
+            .any(|(_, pos)| {
+                self.sim.map.get_current().in_bounds(target)
+                    && DistanceAlg::Pythagoras.distance2d(pos.pt, target)
+                        <= game::inventory::THROW_RANGE
+            })
+    }
+
+    // Movement keys walk the aiming cursor, Enter or F throws.
+    fn throw_input(&mut self, ctx: &mut BTerm, item: usize, target: Point) -> NewState {
+        match ctx.key {
+            Some(VirtualKeyCode::Escape) => NewState::Wait,
+            Some(VirtualKeyCode::Return) | Some(VirtualKeyCode::F) => {
+                self.sim.turn = TurnState::WaitingForInput;
+                self.sim.player_command(Command::Throw { item, target })
+            }
+            Some(key) => {
+                if let Some(Command::Move { delta_x, delta_y }) = game::player::command_for_key(key)
+                {
+                    let target = target + Point::new(delta_x, delta_y);
+                    if self.throw_in_reach(target) {
+                        self.sim.turn = TurnState::Throwing { item, target };
+                    }
+                }
+                NewState::NoChange
+            }
+            None => NewState::NoChange,
+        }
+    }
+
+    // Keep a copy of the commands that led here, for bug reports. A resumed
+    // game can't be rebuilt from its seed, so it isn't recorded.
+    fn record_run(&self) {
+        if self.replay.is_none() && !self.sim.resumed {
+            let _ = Recording::from_simulation(&self.sim).save(crate::replay::RECORDING_FILE);
+        }
     }
 }
 
@@ -112,68 +171,112 @@ impl GameState for State {
         render::clear_all_consoles(ctx);
         ctx.set_active_console(0);
         let (mouse_x, mouse_y) = ctx.mouse_pos();
-        render::render_gui(&mut self.ecs, &self.map, mouse_x, mouse_y, ctx.left_click);
+        let throw_target = match &self.sim.turn {
+            TurnState::Throwing { target, .. } => Some(*target),
+            _ => None,
+        };
+        let clicked = render::render_gui(
+            &mut self.sim.ecs,
+            &self.sim.map,
+            mouse_x,
+            mouse_y,
+            ctx.left_click,
+            throw_target,
+        );
         render_draw_buffer(ctx).expect("Render error");
 
-        let new_state = match &self.turn {
+        let new_state = match &self.sim.turn {
             TurnState::Modal { title, body } => render::modal(ctx, title, body),
-            TurnState::WaitingForInput => game::player_turn(ctx, &mut self.ecs, &mut self.map),
-            TurnState::PlayerTurn => {
-                let mut is_dead = false;
-                <(&Player, &Health)>::query().for_each(&self.ecs, |(_, hp)| {
-                    if hp.current == 0 {
-                        is_dead = true;
+            TurnState::Inventory { mode } => {
+                let mode = *mode;
+                match render::inventory_menu(ctx, &self.sim.ecs, mode) {
+                    render::MenuResult::NoResponse => NewState::NoChange,
+                    render::MenuResult::Cancel => NewState::Wait,
+                    render::MenuResult::Selected(index)
+                        if matches!(mode, InventoryMode::Use)
+                            && game::inventory::is_throwable(&self.sim.ecs, index) =>
+                    {
+                        self.sim.turn = TurnState::Throwing {
+                            item: index,
+                            target: self.throw_start(),
+                        };
+                        NewState::NoChange
+                    }
+                    render::MenuResult::Selected(index) => {
+                        self.sim.turn = TurnState::WaitingForInput;
+                        self.sim.player_command(match mode {
+                            InventoryMode::Use => Command::UseItem(index),
+                            InventoryMode::Drop => Command::DropItem(index),
+                        })
                     }
-                });
-                if is_dead {
-                    NewState::Dead
-                } else {
-                    NewState::Enemy
                 }
-                // TODO: Extra turns for speed boosts could go here
             }
-            TurnState::EnemyTurn => {
-                game::friendly_turn(&mut self.ecs, &mut self.map);
-                game::colonists_turn(&mut self.ecs, &mut self.map);
-                game::monsters_turn(&mut self.ecs, &mut self.map);
-                NewState::WrapUp
+            TurnState::WaitingForInput => {
+                if let Some(replay) = &mut self.replay {
+                    match replay.next_command(ctx.key) {
+                        Some(command) => self.sim.player_command(command),
+                        None => NewState::NoChange,
+                    }
+                } else {
+                    game::player_turn(ctx, &mut self.sim, clicked)
+                }
             }
-            TurnState::WrapUpTurn => {
-                game::timed_events::manage_event_timers(&mut self.ecs, &self.map);
-                game::explosions::process_explosions(&mut self.ecs, &mut self.map);
-                game::dialog::spawn_dialog(&mut self.ecs);
-                game::turn_check::end_of_turn(&mut self.ecs)
+            TurnState::Throwing { item, target } => {
+                let (item, target) = (*item, *target);
+                self.throw_input(ctx, item, target)
             }
+            TurnState::SaveFailed { error } => render::save_failed(ctx, error),
             TurnState::GameOver { reason } => match reason {
-                GameOverType::Dead => render::game_over_dead(ctx, &self.ecs),
-                GameOverType::Left => render::game_over_left(ctx, &self.ecs),
+                GameOverType::Dead => render::game_over_dead(ctx, &self.sim.ecs),
+                GameOverType::Left => render::game_over_left(ctx, &self.sim.ecs),
             },
+            _ => self.sim.step(),
         };
         match new_state {
-            NewState::NoChange => {}
-            NewState::Wait => self.turn = TurnState::WaitingForInput,
-            NewState::Enemy => self.turn = TurnState::EnemyTurn,
-            NewState::WrapUp => self.turn = TurnState::WrapUpTurn,
-            NewState::Player => self.turn = TurnState::PlayerTurn,
-            NewState::LeftMap => {
-                self.turn = TurnState::GameOver {
-                    reason: GameOverType::Left,
-                }
-            }
-            NewState::Dead => {
-                self.turn = TurnState::GameOver {
-                    reason: GameOverType::Dead,
+            NewState::Restart => self.restart_game(),
+            NewState::SaveAndQuit => {
+                self.record_run();
+                match save::save_game(&self.sim) {
+                    Ok(()) => ctx.quit(),
+                    Err(e) => {
+                        self.sim.turn = TurnState::SaveFailed {
+                            error: e.to_string(),
+                        }
+                    }
                 }
             }
-            NewState::Restart => {
-                self.turn = self.restart_game();
-            }
+            NewState::Quit => ctx.quit(),
             NewState::Help => {
-                self.turn = TurnState::Modal{
+                self.sim.turn = TurnState::Modal{
                     title: "Commands".to_string(),
-                    body: "WASD or Cursor Keys to move. T to cycle target. F to fire your gun at them. Mouse over entities for a description, click them to set them as the current target.".to_string()
+                    body: "WASD or Cursor Keys to move, or to attack whatever is in the way. T to cycle target. F to fire your gun at them. Q to switch weapons, R to reload or vent them. G to pick up items, I to use them and X to drop them. ESC to save and quit; closing the window quits without saving. Mouse over entities for a description, click them to set them as the current target.".to_string()
                 };
             }
+            NewState::Inventory => {
+                self.sim.turn = TurnState::Inventory {
+                    mode: InventoryMode::Use,
+                }
+            }
+            NewState::DropMenu => {
+                self.sim.turn = TurnState::Inventory {
+                    mode: InventoryMode::Drop,
+                }
+            }
+            NewState::Dead | NewState::LeftMap => {
+                self.record_run();
+                self.sim.apply(&new_state);
+            }
+            _ => self.sim.apply(&new_state),
+        }
+
+        // Once a replay runs out of commands, hand control back to the player
+        let replay_done = self.replay.as_ref().map_or(false, |r| r.is_finished());
+        if replay_done && self.sim.is_waiting_for_input() {
+            self.replay = None;
+            self.sim.turn = TurnState::Modal {
+                title: "Replay Complete".to_string(),
+                body: text::REPLAY_DONE.to_string(),
+            };
         }
     }
 }